@@ -0,0 +1,173 @@
+extern crate minesweeper;
+
+use minesweeper::engine::board::{Board, GameStatus};
+use minesweeper::engine::bot::{Bot, Strategy};
+use minesweeper::engine::metrics;
+use std::env;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+const USAGE: &str = "Usage: minesweeper-simulate [--games <n>] [--width <cells>] \
+    [--height <cells>] [--mines <n>] [--strategy safest|random] [--threads <n>]";
+
+struct Options {
+    games: u32,
+    width: u32,
+    height: u32,
+    mines: u32,
+    strategy: Strategy,
+    threads: u32,
+}
+
+// Totals of the games one thread played
+#[derive(Default)]
+struct Results {
+    games: u32,
+    wins: u32,
+    three_bv: u64,
+    guesses: u64,
+    rough_guesses: u64,
+}
+
+pub fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let start = Instant::now();
+    let results = simulate(&options);
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let games = results.games.max(1) as f64;
+    println!(
+        "{} games of {}x{} with {} mines, {:?} strategy on {} threads",
+        results.games,
+        options.width,
+        options.height,
+        options.mines,
+        options.strategy,
+        options.threads
+    );
+    println!(
+        "Win rate:          {:.2}% ({} won)",
+        results.wins as f64 * 100f64 / games,
+        results.wins
+    );
+    println!("Average 3BV:       {:.2}", results.three_bv as f64 / games);
+    println!("Average guesses:   {:.2}", results.guesses as f64 / games);
+    println!(
+        "Rough guesses:     {:.2}",
+        results.rough_guesses as f64 / games
+    );
+    println!(
+        "Throughput:        {:.1} games/s ({:.2}s)",
+        results.games as f64 / elapsed,
+        elapsed
+    );
+}
+
+// Threads take games off a shared counter until all are played
+fn simulate(options: &Options) -> Results {
+    let next_game = Arc::new(AtomicU32::new(0));
+    let handles: Vec<_> = (0..options.threads)
+        .map(|_| {
+            let next_game = Arc::clone(&next_game);
+            let (games, width, height, mines, strategy) = (
+                options.games,
+                options.width,
+                options.height,
+                options.mines,
+                options.strategy,
+            );
+            thread::spawn(move || {
+                let mut results = Results::default();
+                while next_game.fetch_add(1, Ordering::Relaxed) < games {
+                    let mut board = Board::new(width, height, mines);
+                    let mut bot = Bot::with_strategy(strategy);
+                    results.three_bv += metrics::three_bv(&board) as u64;
+
+                    while let Some(next) = bot.next_move(&board) {
+                        if next.is_guess {
+                            results.guesses += 1;
+                        }
+                        if next.is_rough {
+                            results.rough_guesses += 1;
+                        }
+                        board.click(next.cell.0, next.cell.1, next.action);
+                    }
+
+                    results.games += 1;
+                    if *board.get_status() == GameStatus::Won {
+                        results.wins += 1;
+                    }
+                }
+                results
+            })
+        })
+        .collect();
+
+    let mut total = Results::default();
+    for handle in handles {
+        let results = handle.join().expect("Simulation thread panicked");
+        total.games += results.games;
+        total.wins += results.wins;
+        total.three_bv += results.three_bv;
+        total.guesses += results.guesses;
+        total.rough_guesses += results.rough_guesses;
+    }
+    total
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        games: 1000,
+        width: 30,
+        height: 16,
+        mines: 99,
+        strategy: Strategy::Safest,
+        threads: thread::available_parallelism().map_or(1, |n| n.get() as u32),
+    };
+
+    let mut arguments = env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let value = arguments
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", argument))?;
+        let number = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid value for {}: {}", argument, value))
+        };
+        match argument.as_str() {
+            "--games" => options.games = number()?,
+            "--width" => options.width = number()?,
+            "--height" => options.height = number()?,
+            "--mines" => options.mines = number()?,
+            "--threads" => options.threads = number()?.max(1),
+            "--strategy" => {
+                options.strategy = Strategy::from_name(&value)
+                    .ok_or_else(|| format!("Unknown strategy: {}", value))?
+            }
+            _ => return Err(format!("Unknown argument: {}", argument)),
+        }
+    }
+
+    if options.width == 0 || options.height == 0 {
+        return Err("Board has to have at least one cell".to_string());
+    }
+    let cells = options
+        .width
+        .checked_mul(options.height)
+        .ok_or_else(|| "Board is too big".to_string())?;
+    if options.mines >= cells {
+        return Err("Board needs at least one cell without a mine".to_string());
+    }
+    Ok(options)
+}
@@ -0,0 +1,198 @@
+extern crate crossterm;
+extern crate minesweeper;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+    MouseEventKind,
+};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use minesweeper::engine::board::{Action, Board, GameStatus};
+use minesweeper::engine::field::*;
+use std::io::{self, Write};
+use std::time::Duration;
+
+// Same board as the SDL version
+const BOARD_PARAMS: (u32, u32, u32) = (20, 15, 50);
+// Terminal columns taken by one cell, keeps cells roughly square
+const CELL_WIDTH: u16 = 2;
+// Board starts below the status line
+const BOARD_TOP: u16 = 2;
+// Longest wait for input, often enough to keep the timer ticking
+const INPUT_TIMEOUT_MS: u64 = 200;
+
+const HELP: &str = "arrows/hjkl move  space reveal  f flag  c chord  r restart  q quit";
+
+// Puts the terminal back the way it was, also when drawing fails halfway
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(
+            io::stdout(),
+            Show,
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("Terminal error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+fn new_board() -> Board {
+    Board::new(BOARD_PARAMS.0, BOARD_PARAMS.1, BOARD_PARAMS.2)
+}
+
+fn run() -> io::Result<()> {
+    let _guard = TerminalGuard::new()?;
+    let mut stdout = io::stdout();
+
+    let mut board = new_board();
+    let mut cursor = (0i32, 0i32);
+
+    loop {
+        // Everything is redrawn anyway, changed cells only matter to the SDL version
+        board.take_changed_cells();
+        render(&mut stdout, &board, cursor)?;
+
+        if !event::poll(Duration::from_millis(INPUT_TIMEOUT_MS))? {
+            continue;
+        }
+
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => {
+                let width = *board.get_width() as i32;
+                let height = *board.get_height() as i32;
+                match key.code {
+                    KeyCode::Left | KeyCode::Char('h') => cursor.0 = (cursor.0 - 1).max(0),
+                    KeyCode::Right | KeyCode::Char('l') => cursor.0 = (cursor.0 + 1).min(width - 1),
+                    KeyCode::Up | KeyCode::Char('k') => cursor.1 = (cursor.1 - 1).max(0),
+                    KeyCode::Down | KeyCode::Char('j') => cursor.1 = (cursor.1 + 1).min(height - 1),
+                    KeyCode::Char(' ') | KeyCode::Enter => {
+                        board.click(cursor.0, cursor.1, Action::Reveal);
+                    }
+                    KeyCode::Char('f') => {
+                        board.click(cursor.0, cursor.1, Action::Mark);
+                    }
+                    KeyCode::Char('c') => {
+                        board.click(cursor.0, cursor.1, Action::Chord);
+                    }
+                    KeyCode::Char('r') => board = new_board(),
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    _ => {}
+                }
+            }
+            Event::Mouse(mouse) => {
+                let cell = (
+                    (mouse.column / CELL_WIDTH) as i32,
+                    mouse.row as i32 - BOARD_TOP as i32,
+                );
+                if board.get_cell_index(cell.0, cell.1).is_none() {
+                    continue;
+                }
+
+                match mouse.kind {
+                    MouseEventKind::Down(button) => {
+                        cursor = cell;
+                        let action = match button {
+                            MouseButton::Left => Action::Reveal,
+                            MouseButton::Right => Action::Mark,
+                            MouseButton::Middle => Action::Chord,
+                        };
+                        board.click(cell.0, cell.1, action);
+                    }
+                    MouseEventKind::Moved => cursor = cell,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+// Whole board is drawn over the previous one every time, it's only a few kilobytes of output
+fn render(stdout: &mut io::Stdout, board: &Board, cursor: (i32, i32)) -> io::Result<()> {
+    let status = match board.get_status() {
+        GameStatus::Playing => "",
+        GameStatus::Won => "Victory! Press R to restart.",
+        GameStatus::Lost => "Defeat. Press R to restart.",
+    };
+    let mines_left = *board.get_mines_count() as i32 - board.get_marked_count() as i32;
+    queue!(
+        stdout,
+        MoveTo(0, 0),
+        ResetColor,
+        Clear(ClearType::CurrentLine),
+        Print(format!(
+            "Mines: {:3}  Time: {:03}  {}",
+            mines_left,
+            board.get_elapsed_time().as_secs(),
+            status
+        )),
+    )?;
+
+    for y in 0..*board.get_height() as i32 {
+        queue!(stdout, MoveTo(0, BOARD_TOP + y as u16))?;
+        for x in 0..*board.get_width() as i32 {
+            let field = board.get_fields_array()[board.arr2d_arr1d(x, y)];
+            let background = if (x, y) == cursor {
+                Color::DarkGrey
+            } else {
+                Color::Reset
+            };
+            queue!(
+                stdout,
+                SetBackgroundColor(background),
+                SetForegroundColor(field_color(&field)),
+                Print(format!("{:<width$}", field, width = CELL_WIDTH as usize)),
+            )?;
+        }
+        queue!(stdout, ResetColor)?;
+    }
+
+    queue!(
+        stdout,
+        MoveTo(0, BOARD_TOP + *board.get_height() as u16 + 1),
+        Print(HELP),
+    )?;
+    stdout.flush()
+}
+
+// Glyphs are the plain-text board format's, only colored
+fn field_color(field: &Field) -> Color {
+    match (field.field_status, field.field_type) {
+        (FieldStatus::Unrevealed, _) if field.is_marked => Color::Magenta,
+        (FieldStatus::Unrevealed, _) => Color::Grey,
+        (FieldStatus::Revealed, FieldType::Empty) => Color::Reset,
+        (FieldStatus::Revealed, FieldType::Mine) => Color::Red,
+        // Classic minesweeper colors, one per count
+        (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => match mines_nearby {
+            1 => Color::Blue,
+            2 => Color::Green,
+            3 => Color::Red,
+            4 => Color::DarkBlue,
+            5 => Color::DarkRed,
+            6 => Color::Cyan,
+            7 => Color::White,
+            _ => Color::DarkGrey,
+        },
+    }
+}
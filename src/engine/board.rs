@@ -0,0 +1,817 @@
+extern crate rand;
+
+use crate::engine::field::*;
+use crate::engine::layout::LayoutError;
+use crate::engine::luck;
+use crate::engine::solver;
+
+use std::time::{Duration, Instant};
+
+// How the edges of the board connect
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Topology {
+    // Cells on the edges have fewer neighbours
+    Bounded,
+    // Left edge touches the right one and the top touches the bottom
+    Torus,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameStatus {
+    Lost,
+    Won,
+    Playing,
+}
+
+// What the player does with a cell, frontends map their input onto these
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Action {
+    // Chords instead when the cell is already revealed
+    Reveal,
+    Mark,
+    Chord,
+}
+
+// What a click did, used to give feedback like sounds
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClickOutcome {
+    Nothing,
+    // Single field revealed
+    Reveal,
+    // More than one field revealed at once
+    Cascade,
+    // Mark put on or taken off
+    Flag,
+    Chord,
+    Won,
+    Lost,
+}
+
+// What a click achieved, tallied for the efficiency statistics
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClickKind {
+    UsefulReveal,
+    // Nothing changed, e.g. a click on a revealed cell with nothing left around it
+    Wasted,
+    Flag,
+    Unflag,
+    Chord,
+    // Chord on a number with hidden cells around it that didn't match its flags
+    FailedChord,
+}
+
+// Click made while playing, the log of them gives the statistics at the end of the game
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LoggedClick {
+    pub cell: (i32, i32),
+    // Reveal on a revealed cell is logged as the chord it turns into
+    pub action: Action,
+    pub kind: ClickKind,
+    pub outcome: ClickOutcome,
+    // Since the timer started
+    pub time: Duration,
+}
+
+// Rules of the game without any rendering, shared by every frontend
+#[derive(Clone)]
+pub struct Board {
+    game_width: u32,
+    game_height: u32,
+
+    game_fields_array: Vec<Field>,
+    game_mines_count: u32,
+
+    game_status: GameStatus,
+
+    // Cells changed since they were last taken, frontends redraw only these
+    game_changed_cells: Vec<usize>,
+
+    // Timer starts with the first click and stops when the game ends
+    game_start_time: Option<Instant>,
+    game_end_time: Option<Instant>,
+
+    game_click_log: Vec<LoggedClick>,
+
+    // A forced guess that hits a mine moves the mine away instead of ending the game
+    game_forgiving: bool,
+
+    game_topology: Topology,
+}
+
+impl Board {
+    pub fn new(game_width: u32, game_height: u32, game_mines_count: u32) -> Board {
+        let mut board = Board {
+            game_width,
+            game_height,
+            game_fields_array: Vec::with_capacity(game_width as usize * game_height as usize),
+            game_mines_count,
+            game_status: GameStatus::Playing,
+            game_changed_cells: Vec::new(),
+            game_start_time: None,
+            game_end_time: None,
+            game_click_log: Vec::new(),
+            game_forgiving: false,
+            game_topology: Topology::Bounded,
+        };
+
+        board.setup_mines();
+        board.setup_pointers();
+        board
+    }
+
+    // Board with a known state, e.g. parsed from text, mines are counted from the fields
+    pub fn from_fields(game_width: u32, game_height: u32, game_fields_array: Vec<Field>) -> Board {
+        let game_mines_count = game_fields_array
+            .iter()
+            .filter(|f| f.field_type == FieldType::Mine)
+            .count() as u32;
+
+        Board {
+            game_width,
+            game_height,
+            game_fields_array,
+            game_mines_count,
+            game_status: GameStatus::Playing,
+            game_changed_cells: Vec::new(),
+            game_start_time: None,
+            game_end_time: None,
+            game_click_log: Vec::new(),
+            game_forgiving: false,
+            game_topology: Topology::Bounded,
+        }
+    }
+
+    // Fresh board with the given mines, counts around them come from setup_pointers
+    pub fn with_mines(
+        game_width: u32,
+        game_height: u32,
+        mines: &[(u32, u32)],
+    ) -> Result<Board, LayoutError> {
+        if game_width == 0 || game_height == 0 {
+            return Err(LayoutError::Empty);
+        }
+
+        let mut fields = vec![
+            Field {
+                field_status: FieldStatus::Unrevealed,
+                field_type: FieldType::Empty,
+                is_marked: false,
+            };
+            game_width as usize * game_height as usize
+        ];
+        for &(x, y) in mines {
+            if x >= game_width || y >= game_height {
+                return Err(LayoutError::OutOfBounds { x, y });
+            }
+
+            let field = &mut fields[(x + y * game_width) as usize];
+            if field.field_type == FieldType::Mine {
+                return Err(LayoutError::DuplicateMine { x, y });
+            }
+            field.field_type = FieldType::Mine;
+        }
+
+        let mut board = Board::from_fields(game_width, game_height, fields);
+        board.setup_pointers();
+        Ok(board)
+    }
+
+    pub fn get_mine_positions(&self) -> Vec<(u32, u32)> {
+        self.get_fields_array()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.field_type == FieldType::Mine)
+            .map(|(index, _)| {
+                let (x, y) = self.arr1d_arr2d(index);
+                (x as u32, y as u32)
+            })
+            .collect()
+    }
+
+    pub fn get_width(&self) -> &u32 {
+        &self.game_width
+    }
+    pub fn get_height(&self) -> &u32 {
+        &self.game_height
+    }
+    pub fn get_mines_count(&self) -> &u32 {
+        &self.game_mines_count
+    }
+    // Hidden mines of a board parsed from a player's view can't be counted, callers know the total
+    pub fn set_mines_count(&mut self, game_mines_count: u32) {
+        self.game_mines_count = game_mines_count;
+    }
+    pub fn get_fields_array(&self) -> &Vec<Field> {
+        &self.game_fields_array
+    }
+    pub fn get_status(&self) -> &GameStatus {
+        &self.game_status
+    }
+
+    pub fn get_marked_count(&self) -> u32 {
+        self.game_fields_array
+            .iter()
+            .filter(|f| f.is_marked)
+            .count() as u32
+    }
+
+    pub fn get_elapsed_time(&self) -> Duration {
+        match (self.game_start_time, self.game_end_time) {
+            (Some(start), Some(end)) => end - start,
+            (Some(start), None) => start.elapsed(),
+            _ => Duration::from_secs(0),
+        }
+    }
+
+    pub fn is_forgiving(&self) -> bool {
+        self.game_forgiving
+    }
+
+    pub fn set_forgiving(&mut self, forgiving: bool) {
+        self.game_forgiving = forgiving;
+    }
+
+    pub fn get_topology(&self) -> &Topology {
+        &self.game_topology
+    }
+
+    // Counts depend on the topology, so it's set before the game starts
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.game_topology = topology;
+        for field in self.game_fields_array.iter_mut() {
+            if field.field_type != FieldType::Mine {
+                field.field_type = FieldType::Empty;
+            }
+        }
+        self.setup_pointers();
+    }
+
+    pub fn get_click_log(&self) -> &Vec<LoggedClick> {
+        &self.game_click_log
+    }
+
+    // Cells changed since the last call
+    pub fn take_changed_cells(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.game_changed_cells)
+    }
+
+    pub fn arr2d_arr1d(&self, x: i32, y: i32) -> usize {
+        (x + y * self.game_width as i32) as usize
+    }
+
+    pub fn arr1d_arr2d(&self, index: usize) -> (i32, i32) {
+        (
+            (index as u32 % self.game_width) as i32,
+            (index as u32 / self.game_width) as i32,
+        )
+    }
+
+    pub fn get_cell_index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as u32 > self.game_width - 1 || y as u32 > self.game_height - 1 {
+            None
+        } else {
+            Some(self.arr2d_arr1d(x, y))
+        }
+    }
+
+    // Cells around the cell, across the edges on a torus
+    pub fn get_neighbours(&self, index: usize) -> Vec<usize> {
+        let (x, y) = self.arr1d_arr2d(index);
+        let (width, height) = (self.game_width as i32, self.game_height as i32);
+        let mut neighbours = Vec::with_capacity(8);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let neighbour = match self.game_topology {
+                    Topology::Bounded => self.get_cell_index(x + dx, y + dy),
+                    Topology::Torus => {
+                        self.get_cell_index((x + dx).rem_euclid(width), (y + dy).rem_euclid(height))
+                    }
+                };
+                // Boards narrower than three cells wrap onto the same cells twice
+                if let Some(neighbour) = neighbour {
+                    if neighbour != index && !neighbours.contains(&neighbour) {
+                        neighbours.push(neighbour);
+                    }
+                }
+            }
+        }
+        neighbours
+    }
+
+    // Steps between two cells going any way including diagonally, used for rings of
+    // animations spreading from a click
+    pub fn get_distance(&self, a: (i32, i32), b: (i32, i32)) -> u32 {
+        let along = |a: i32, b: i32, size: u32| {
+            let distance = (a - b).unsigned_abs();
+            match self.game_topology {
+                Topology::Bounded => distance,
+                Topology::Torus => distance.min(size - distance),
+            }
+        };
+        along(a.0, b.0, self.game_width).max(along(a.1, b.1, self.game_height))
+    }
+
+    fn surrounding_cells(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        match self.get_cell_index(x, y) {
+            Some(index) => self
+                .get_neighbours(index)
+                .into_iter()
+                .map(|neighbour| self.arr1d_arr2d(neighbour))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn setup_mines(&mut self) {
+        let mut empty_fields: Vec<usize> = Vec::with_capacity(self.game_fields_array.len());
+        for i in 0..self.game_width as usize * self.game_height as usize {
+            self.game_fields_array.push(Field {
+                field_status: FieldStatus::Unrevealed,
+                field_type: FieldType::Empty,
+                is_marked: false,
+            });
+            empty_fields.push(i);
+        }
+
+        for _ in 0..self.game_mines_count {
+            let index = ((rand::random::<u64>() / 160u64 + empty_fields.len() as u64 * 37u64)
+                % empty_fields.len() as u64) as usize;
+            self.game_fields_array[empty_fields[index]].field_type = FieldType::Mine;
+            empty_fields.remove(index);
+        }
+    }
+
+    fn setup_pointers(&mut self) {
+        for x in 0..self.game_width as i32 {
+            for y in 0..self.game_height as i32 {
+                let middle_cell_index_index = self.get_cell_index(x, y);
+
+                if middle_cell_index_index.is_none() {
+                    continue;
+                }
+
+                if self.game_fields_array[middle_cell_index_index.unwrap()].field_type
+                    == FieldType::Mine
+                {
+                    let surrounding_cell_indexs = self.surrounding_cells(x, y);
+
+                    for field in surrounding_cell_indexs.iter() {
+                        let index = self.get_cell_index(field.0, field.1);
+
+                        if let Some(i) = index {
+                            match self.game_fields_array[i].field_type {
+                                FieldType::Empty => {
+                                    self.game_fields_array[i].field_type =
+                                        FieldType::Pointer { mines_nearby: 1 };
+                                }
+                                FieldType::Pointer { mines_nearby } => {
+                                    self.game_fields_array[i].field_type = FieldType::Pointer {
+                                        mines_nearby: mines_nearby + 1,
+                                    };
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Lucky mode: frontier cells around the mine are laid out again so every number the
+    // player has seen stays true. When no layout fits, the numbers proved the cell is a
+    // mine and the mine stays
+    fn relocate_mine(&mut self, index: usize) {
+        let layout = match luck::rearrange(self, index) {
+            Some(layout) => layout,
+            None => return,
+        };
+        debug_assert!(self.agrees_with_numbers(&layout));
+
+        // Revealed numbers end up the same, they don't need redrawing
+        let first_changed = self.game_changed_cells.len();
+        for (i, is_mine) in layout.into_iter().enumerate() {
+            let was_mine = self.game_fields_array[i].field_type == FieldType::Mine;
+            if was_mine && !is_mine {
+                self.remove_mine(i);
+            } else if is_mine && !was_mine {
+                self.place_mine(i);
+            }
+        }
+        self.game_changed_cells.truncate(first_changed);
+    }
+
+    // Whether the mines would leave every revealed cell safe and showing the same number
+    pub(crate) fn agrees_with_numbers(&self, layout: &[bool]) -> bool {
+        (0..self.game_fields_array.len()).all(|i| {
+            let field = self.game_fields_array[i];
+            let mines_nearby = match (field.field_status, field.field_type) {
+                (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => mines_nearby,
+                (FieldStatus::Revealed, _) => 0,
+                _ => return true,
+            };
+            let mines_around = self
+                .get_neighbours(i)
+                .into_iter()
+                .filter(|neighbour| layout[*neighbour])
+                .count();
+            !layout[i] && mines_around == mines_nearby as usize
+        })
+    }
+
+    // Counts are updated only around the cell instead of redoing setup_pointers
+    fn place_mine(&mut self, index: usize) {
+        self.game_fields_array[index].field_type = FieldType::Mine;
+        for neighbour in self.get_neighbours(index) {
+            let mines_nearby = match self.game_fields_array[neighbour].field_type {
+                FieldType::Mine => continue,
+                FieldType::Empty => 1,
+                FieldType::Pointer { mines_nearby } => mines_nearby + 1,
+            };
+            self.set_count(neighbour, mines_nearby);
+        }
+    }
+
+    fn remove_mine(&mut self, index: usize) {
+        let mut mines_around = 0;
+        for neighbour in self.get_neighbours(index) {
+            let mines_nearby = match self.game_fields_array[neighbour].field_type {
+                FieldType::Mine => {
+                    mines_around += 1;
+                    continue;
+                }
+                FieldType::Empty => 0,
+                FieldType::Pointer { mines_nearby } => mines_nearby - 1,
+            };
+            self.set_count(neighbour, mines_nearby);
+        }
+        self.set_count(index, mines_around);
+    }
+
+    fn set_count(&mut self, index: usize, mines_nearby: u8) {
+        self.game_fields_array[index].field_type = if mines_nearby == 0 {
+            FieldType::Empty
+        } else {
+            FieldType::Pointer { mines_nearby }
+        };
+        if self.game_fields_array[index].field_status == FieldStatus::Revealed {
+            self.game_changed_cells.push(index);
+        }
+    }
+
+    fn flood_reveal(&mut self, x: i32, y: i32) {
+        let surrounding_cell_indexes = self.surrounding_cells(x, y);
+
+        let cell_index = self.get_cell_index(x, y);
+
+        if let Some(c) = cell_index {
+            if self.game_fields_array[c].field_status == FieldStatus::Revealed {
+                return;
+            } else {
+                self.game_fields_array[c].field_status = FieldStatus::Revealed;
+                self.game_changed_cells.push(c);
+                if let FieldType::Pointer { .. } = self.game_fields_array[c].field_type {
+                    self.chord(x, y);
+                    return;
+                }
+            }
+        } else {
+            return;
+        }
+
+        for field in surrounding_cell_indexes.iter() {
+            self.flood_reveal(field.0, field.1);
+        }
+    }
+
+    // Reveals unmarked neighbours of a revealed pointer once all its mines are marked
+    fn chord(&mut self, x: i32, y: i32) {
+        let mines_nearby = match self.get_cell_index(x, y).map(|c| self.game_fields_array[c]) {
+            Some(Field {
+                field_status: FieldStatus::Revealed,
+                field_type: FieldType::Pointer { mines_nearby },
+                ..
+            }) => mines_nearby,
+            _ => return,
+        };
+
+        let surrounding_cell_indexes = self.surrounding_cells(x, y);
+
+        let mut marked_around = 0;
+        for cell_index in surrounding_cell_indexes.iter() {
+            if let Some(index) = self.get_cell_index(cell_index.0, cell_index.1) {
+                if self.game_fields_array[index].is_marked {
+                    marked_around += 1;
+                }
+            }
+        }
+
+        if marked_around == mines_nearby {
+            for cell_index in surrounding_cell_indexes.iter() {
+                if let Some(index) = self.get_cell_index(cell_index.0, cell_index.1) {
+                    if !self.game_fields_array[index].is_marked {
+                        self.flood_reveal(cell_index.0, cell_index.1);
+                    }
+                }
+            }
+        }
+    }
+
+    // Revealed number next to cells that are neither revealed nor marked
+    fn has_hidden_neighbour(&self, x: i32, y: i32) -> bool {
+        match self.get_cell_index(x, y).map(|c| self.game_fields_array[c]) {
+            Some(Field {
+                field_status: FieldStatus::Revealed,
+                field_type: FieldType::Pointer { .. },
+                ..
+            }) => {}
+            _ => return false,
+        }
+
+        self.surrounding_cells(x, y).iter().any(|cell_index| {
+            self.get_cell_index(cell_index.0, cell_index.1)
+                .map(|index| self.game_fields_array[index])
+                .is_some_and(|f| f.field_status != FieldStatus::Revealed && !f.is_marked)
+        })
+    }
+
+    fn mark_field(&mut self, x: i32, y: i32) {
+        let index = self.get_cell_index(x, y);
+        if let Some(i) = index {
+            if self.game_fields_array[i].field_status == FieldStatus::Revealed {
+                return;
+            }
+
+            self.game_fields_array[i].is_marked = !self.game_fields_array[i].is_marked;
+            self.game_changed_cells.push(i);
+        }
+    }
+
+    pub fn click(&mut self, x: i32, y: i32, action: Action) -> ClickOutcome {
+        if self.game_status != GameStatus::Playing {
+            return ClickOutcome::Nothing;
+        }
+
+        let clicked_index = match self.get_cell_index(x, y) {
+            Some(index) => index,
+            None => return ClickOutcome::Nothing,
+        };
+        let clicked_field = self.game_fields_array[clicked_index];
+
+        let first_changed = self.game_changed_cells.len();
+        let mut outcome = ClickOutcome::Nothing;
+        let mut logged_action = action;
+        match action {
+            Action::Reveal | Action::Chord
+                if clicked_field.field_status == FieldStatus::Revealed =>
+            {
+                logged_action = Action::Chord;
+                self.chord(x, y);
+                if self.game_changed_cells.len() > first_changed {
+                    outcome = ClickOutcome::Chord;
+                }
+            }
+            Action::Reveal => {
+                if self.game_forgiving
+                    && clicked_field.field_type == FieldType::Mine
+                    && solver::is_guess_forced(self)
+                {
+                    self.relocate_mine(clicked_index);
+                }
+                self.flood_reveal(x, y);
+                outcome = match self.game_changed_cells.len() - first_changed {
+                    0 => ClickOutcome::Nothing,
+                    1 => ClickOutcome::Reveal,
+                    _ => ClickOutcome::Cascade,
+                };
+            }
+            Action::Mark => {
+                self.mark_field(x, y);
+                if self.game_changed_cells.len() > first_changed {
+                    outcome = ClickOutcome::Flag;
+                }
+            }
+            Action::Chord => {}
+        }
+
+        let changed = self.game_changed_cells.len() > first_changed;
+        let kind = if !changed {
+            if logged_action == Action::Chord && self.has_hidden_neighbour(x, y) {
+                ClickKind::FailedChord
+            } else {
+                ClickKind::Wasted
+            }
+        } else {
+            match logged_action {
+                Action::Reveal => ClickKind::UsefulReveal,
+                Action::Mark if self.game_fields_array[clicked_index].is_marked => ClickKind::Flag,
+                Action::Mark => ClickKind::Unflag,
+                Action::Chord => ClickKind::Chord,
+            }
+        };
+
+        if self.game_start_time.is_none() {
+            self.game_start_time = Some(Instant::now());
+        }
+
+        let revealed: Vec<usize> = self.game_changed_cells[first_changed..]
+            .iter()
+            .cloned()
+            .filter(|i| self.game_fields_array[*i].field_status == FieldStatus::Revealed)
+            .collect();
+        self.check_winning_conditions(&revealed);
+        let outcome = match self.game_status {
+            GameStatus::Playing => outcome,
+            GameStatus::Won => {
+                self.game_end_time = Some(Instant::now());
+                ClickOutcome::Won
+            }
+            GameStatus::Lost => {
+                self.game_end_time = Some(Instant::now());
+                ClickOutcome::Lost
+            }
+        };
+
+        self.game_click_log.push(LoggedClick {
+            cell: (x, y),
+            action: logged_action,
+            kind,
+            outcome,
+            time: self.get_elapsed_time(),
+        });
+        outcome
+    }
+
+    pub fn set_all_visible(&mut self) {
+        for (index, item) in self.game_fields_array.iter_mut().enumerate() {
+            if item.field_status != FieldStatus::Revealed {
+                item.field_status = FieldStatus::Revealed;
+                self.game_changed_cells.push(index);
+            }
+        }
+    }
+
+    fn check_winning_conditions(&mut self, revealed: &[usize]) {
+        // if loosing, chording next to a wrong mark can reveal a mine too
+        if revealed
+            .iter()
+            .any(|i| self.game_fields_array[*i].field_type == FieldType::Mine)
+        {
+            // Reveal all mines
+            for (index, item) in self.game_fields_array.iter_mut().enumerate() {
+                if item.field_type == FieldType::Mine && item.field_status != FieldStatus::Revealed
+                {
+                    item.field_status = FieldStatus::Revealed;
+                    self.game_changed_cells.push(index);
+                }
+            }
+
+            self.game_status = GameStatus::Lost;
+        } else {
+            let mut marked_mines = 0;
+            for item in self.game_fields_array.iter() {
+                if item.field_type == FieldType::Mine && item.is_marked {
+                    marked_mines += 1;
+                }
+            }
+
+            if marked_mines == self.game_mines_count {
+                self.game_status = GameStatus::Won;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_layout_gets_its_counts() {
+        let board = Board::with_mines(3, 3, &[(0, 0), (2, 2)]).unwrap();
+        assert_eq!(*board.get_mines_count(), 2);
+        assert_eq!(format!("{:#}", board), "*1 \n121\n 1*\n");
+    }
+
+    #[test]
+    fn fixed_layout_plays_the_same_every_time() {
+        let layout = Board::with_mines(4, 4, &[(3, 0), (0, 3)]).unwrap();
+        let mut first = layout.clone();
+        let mut second = layout.clone();
+        assert_eq!(first.click(0, 0, Action::Reveal), ClickOutcome::Cascade);
+        assert_eq!(second.click(0, 0, Action::Reveal), ClickOutcome::Cascade);
+        assert_eq!(first.to_string(), second.to_string());
+        assert_eq!(first.click(3, 0, Action::Reveal), ClickOutcome::Lost);
+    }
+
+    fn torus(width: u32, height: u32, mines: &[(u32, u32)]) -> Board {
+        let mut board = Board::with_mines(width, height, mines).unwrap();
+        board.set_topology(Topology::Torus);
+        board
+    }
+
+    fn sorted_neighbours(board: &Board, index: usize) -> Vec<usize> {
+        let mut neighbours = board.get_neighbours(index);
+        neighbours.sort_unstable();
+        neighbours
+    }
+
+    #[test]
+    fn torus_row_wraps_sideways_only() {
+        let board = torus(5, 1, &[]);
+        assert_eq!(sorted_neighbours(&board, 0), vec![1, 4]);
+        assert_eq!(sorted_neighbours(&board, 2), vec![1, 3]);
+        assert_eq!(board.get_distance((0, 0), (4, 0)), 1);
+
+        let column = torus(1, 5, &[]);
+        assert_eq!(sorted_neighbours(&column, 4), vec![0, 3]);
+        assert_eq!(sorted_neighbours(&torus(2, 1, &[]), 0), vec![1]);
+        assert!(torus(1, 1, &[]).get_neighbours(0).is_empty());
+    }
+
+    #[test]
+    fn torus_counts_every_neighbour_once() {
+        let board = torus(2, 2, &[(0, 0)]);
+        for index in 0..4 {
+            let expected: Vec<usize> = (0..4).filter(|other| *other != index).collect();
+            assert_eq!(sorted_neighbours(&board, index), expected);
+        }
+        assert_eq!(format!("{:#}", board), "*1\n11\n");
+
+        let row = torus(3, 1, &[(0, 0)]);
+        assert_eq!(format!("{:#}", row), "*11\n");
+    }
+
+    #[test]
+    fn forgiving_mode_saves_forced_guesses_on_big_frontiers() {
+        // Every number sees one of three cells around the loop, each cell of the top row
+        // is a mine in one of three layouts and there are too many to enumerate
+        let width = 51;
+        let mines: Vec<(u32, u32)> = (0..width).filter(|x| x % 3 == 1).map(|x| (x, 0)).collect();
+        let mut board = torus(width, 2, &mines);
+        board.set_forgiving(true);
+        for x in 0..width as i32 {
+            assert_eq!(board.click(x, 1, Action::Reveal), ClickOutcome::Reveal);
+        }
+        assert!(solver::is_guess_forced(&board));
+
+        assert_eq!(board.click(1, 0, Action::Reveal), ClickOutcome::Reveal);
+        assert_eq!(*board.get_status(), GameStatus::Playing);
+        assert_eq!(board.get_mine_positions().len(), mines.len());
+    }
+
+    #[test]
+    fn forgiving_mode_saves_flagged_forced_guesses() {
+        // A mine next to the first column and one more in the three cells no number touches
+        let mut board = Board::with_mines(4, 2, &[(1, 0), (3, 0), (3, 1)]).unwrap();
+        board.set_forgiving(true);
+        board.click(0, 0, Action::Reveal);
+        board.click(0, 1, Action::Reveal);
+        board.click(3, 0, Action::Mark);
+        assert!(solver::is_guess_forced(&board));
+
+        assert_eq!(board.click(3, 0, Action::Reveal), ClickOutcome::Reveal);
+        assert_eq!(*board.get_status(), GameStatus::Playing);
+    }
+
+    #[test]
+    fn clicks_are_logged_by_what_they_achieved() {
+        // Column of two mines with a 2 between them, a third mine keeps the game going
+        let mut board = Board::with_mines(6, 3, &[(3, 0), (3, 2), (5, 1)]).unwrap();
+        let clicks = [
+            ((0, 0), Action::Reveal, ClickKind::UsefulReveal),
+            ((0, 0), Action::Reveal, ClickKind::Wasted),
+            ((3, 1), Action::Reveal, ClickKind::UsefulReveal),
+            ((3, 1), Action::Chord, ClickKind::FailedChord),
+            ((3, 0), Action::Mark, ClickKind::Flag),
+            ((3, 0), Action::Mark, ClickKind::Unflag),
+            ((3, 0), Action::Mark, ClickKind::Flag),
+            ((3, 2), Action::Mark, ClickKind::Flag),
+            ((3, 1), Action::Chord, ClickKind::Chord),
+            ((3, 1), Action::Chord, ClickKind::Wasted),
+        ];
+        for ((x, y), action, _) in clicks.iter() {
+            board.click(*x, *y, *action);
+        }
+        assert_eq!(*board.get_status(), GameStatus::Playing);
+
+        let logged: Vec<ClickKind> = board.get_click_log().iter().map(|c| c.kind).collect();
+        let expected: Vec<ClickKind> = clicks.iter().map(|(_, _, kind)| *kind).collect();
+        assert_eq!(logged, expected);
+        // Reveal on a revealed cell turns into a chord
+        assert_eq!(board.get_click_log()[1].action, Action::Chord);
+    }
+
+    #[test]
+    fn rejects_bad_layouts() {
+        assert_eq!(Board::with_mines(0, 3, &[]).err(), Some(LayoutError::Empty));
+        assert_eq!(
+            Board::with_mines(3, 3, &[(3, 1)]).err(),
+            Some(LayoutError::OutOfBounds { x: 3, y: 1 })
+        );
+        assert_eq!(
+            Board::with_mines(3, 3, &[(1, 1), (1, 1)]).err(),
+            Some(LayoutError::DuplicateMine { x: 1, y: 1 })
+        );
+    }
+}
@@ -0,0 +1,129 @@
+extern crate rand;
+
+use crate::engine::board::{Action, Board, GameStatus};
+use crate::engine::field::*;
+use crate::engine::probability;
+use crate::engine::solver;
+
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Move {
+    pub cell: (i32, i32),
+    pub action: Action,
+    // Nothing certain was left, the cell is only the least likely to hide a mine
+    pub is_guess: bool,
+    // The guess went by estimated chances, the frontier was too big to work out exactly
+    pub is_rough: bool,
+}
+
+// How the bot guesses once nothing is certain
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    // Cell least likely to hide a mine
+    Safest,
+    // Any hidden cell, a baseline for the other strategies
+    Random,
+}
+
+impl Strategy {
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        match name {
+            "safest" => Some(Strategy::Safest),
+            "random" => Some(Strategy::Random),
+            _ => None,
+        }
+    }
+}
+
+// Plays by the solver's deductions and guesses when they run out
+pub struct Bot {
+    strategy: Strategy,
+    // Deductions stay true, one solve can feed several moves
+    pending: VecDeque<Move>,
+}
+
+impl Default for Bot {
+    fn default() -> Bot {
+        Bot::new()
+    }
+}
+
+impl Bot {
+    pub fn new() -> Bot {
+        Bot::with_strategy(Strategy::Safest)
+    }
+
+    pub fn with_strategy(strategy: Strategy) -> Bot {
+        Bot {
+            strategy,
+            pending: VecDeque::new(),
+        }
+    }
+
+    // None once the game is over
+    pub fn next_move(&mut self, board: &Board) -> Option<Move> {
+        if *board.get_status() != GameStatus::Playing {
+            return None;
+        }
+
+        while let Some(next) = self.pending.pop_front() {
+            if is_hidden(board, next.cell) {
+                return Some(next);
+            }
+        }
+
+        // Mines get flagged too, the game is only won once all of them are marked
+        for deduction in solver::solve(board) {
+            self.pending.push_back(Move {
+                cell: deduction.cell,
+                action: if deduction.is_mine {
+                    Action::Mark
+                } else {
+                    Action::Reveal
+                },
+                is_guess: false,
+                is_rough: false,
+            });
+        }
+        if let Some(next) = self.pending.pop_front() {
+            return Some(next);
+        }
+
+        let mut is_rough = false;
+        let guess = match self.strategy {
+            Strategy::Safest => {
+                let probabilities = probability::mine_probabilities(board);
+                is_rough = !probabilities.is_exact;
+                probability::safest_cell(&probabilities.cells, board)
+            }
+            Strategy::Random => {
+                let hidden: Vec<usize> = board
+                    .get_fields_array()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| f.field_status == FieldStatus::Unrevealed && !f.is_marked)
+                    .map(|(index, _)| index)
+                    .collect();
+                if hidden.is_empty() {
+                    None
+                } else {
+                    Some(hidden[rand::random::<usize>() % hidden.len()])
+                }
+            }
+        };
+        guess.map(|index| Move {
+            cell: board.arr1d_arr2d(index),
+            action: Action::Reveal,
+            is_guess: true,
+            is_rough,
+        })
+    }
+}
+
+fn is_hidden(board: &Board, cell: (i32, i32)) -> bool {
+    board.get_cell_index(cell.0, cell.1).is_some_and(|index| {
+        let field = board.get_fields_array()[index];
+        field.field_status == FieldStatus::Unrevealed && !field.is_marked
+    })
+}
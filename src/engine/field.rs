@@ -1,5 +1,3 @@
-extern crate sdl2;
-
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FieldStatus {
     Unrevealed,
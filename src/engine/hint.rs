@@ -0,0 +1,94 @@
+use crate::engine::board::{Board, GameStatus};
+use crate::engine::probability;
+use crate::engine::solver;
+
+// Cell worth revealing next and why
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub cell: (i32, i32),
+    // False when no cell is provably safe and this is only the best guess
+    pub is_certain: bool,
+    pub explanation: String,
+}
+
+pub fn hint(board: &Board) -> Option<Hint> {
+    if *board.get_status() != GameStatus::Playing {
+        return None;
+    }
+
+    let safe = solver::solve(board)
+        .into_iter()
+        .find(|deduction| !deduction.is_mine);
+    if let Some(deduction) = safe {
+        return Some(Hint {
+            cell: deduction.cell,
+            is_certain: true,
+            explanation: deduction.to_string(),
+        });
+    }
+
+    let probabilities = probability::mine_probabilities(board);
+    probability::safest_cell(&probabilities.cells, board).map(|index| {
+        let cell = board.arr1d_arr2d(index);
+        let risk = probabilities.cells[index].unwrap_or(0f64) * 100f64;
+        let explanation = if probabilities.is_exact {
+            format!(
+                "{},{} is the safest guess, nothing is certain: {:.0}% chance of a mine",
+                cell.0, cell.1, risk
+            )
+        } else {
+            format!(
+                "{},{} looks like the safest guess, nothing is certain: roughly {:.0}% chance of a mine",
+                cell.0, cell.1, risk
+            )
+        };
+        Hint {
+            cell,
+            is_certain: false,
+            explanation,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::text::player_view;
+
+    #[test]
+    fn certain_hint_explains_the_deduction() {
+        let hint = hint(&player_view("F1.\n", 1)).unwrap();
+        assert_eq!(hint.cell, (2, 0));
+        assert!(hint.is_certain);
+        assert_eq!(
+            hint.explanation,
+            "2,0 is safe: the number at 1,0 has all its mines"
+        );
+    }
+
+    #[test]
+    fn guess_hint_gives_the_chance_of_a_mine() {
+        // One mine next to the numbers and one in the four cells no number touches
+        let hint = hint(&player_view("1...\n1...\n", 2)).unwrap();
+        assert!(hint.cell.0 >= 2);
+        assert!(!hint.is_certain);
+        assert_eq!(
+            hint.explanation,
+            format!(
+                "{},{} is the safest guess, nothing is certain: 25% chance of a mine",
+                hint.cell.0, hint.cell.1
+            )
+        );
+    }
+
+    #[test]
+    fn guess_hint_says_when_the_chance_is_estimated() {
+        // Too many cells next to the numbers to enumerate and no rule finds anything
+        let width = 25;
+        let hidden = ".".repeat(width);
+        let text = format!("{}\n2{}2\n{}\n", hidden, "3".repeat(width - 2), hidden);
+        let hint = hint(&player_view(&text, width as u32)).unwrap();
+        assert!(!hint.is_certain);
+        assert!(hint.explanation.contains("roughly"));
+    }
+}
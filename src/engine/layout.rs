@@ -0,0 +1,193 @@
+use crate::engine::board::{Board, Topology};
+use crate::engine::text::ParseError;
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// Mine layouts in formats other minesweeper tools understand:
+// `.mbf` is width and height in a byte each, mine count as a big-endian u16 and
+// then x and y of every mine in a byte each, anything else is the plain-text grid.
+
+const MBF_HEADER_LENGTH: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum LayoutError {
+    Io(String),
+    // Content doesn't follow its format
+    Format(String),
+    Empty,
+    OutOfBounds { x: u32, y: u32 },
+    DuplicateMine { x: u32, y: u32 },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::Io(message) => write!(f, "Couldn't access the layout: {}", message),
+            LayoutError::Format(message) => write!(f, "Invalid layout: {}", message),
+            LayoutError::Empty => write!(f, "Layout has no cells"),
+            LayoutError::OutOfBounds { x, y } => write!(f, "Mine at {},{} is off the board", x, y),
+            LayoutError::DuplicateMine { x, y } => write!(f, "Mine at {},{} is listed twice", x, y),
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+pub fn read_mbf(bytes: &[u8]) -> Result<Board, LayoutError> {
+    if bytes.len() < MBF_HEADER_LENGTH {
+        return Err(LayoutError::Format("MBF header is too short".to_string()));
+    }
+
+    let width = bytes[0] as u32;
+    let height = bytes[1] as u32;
+    let mines_count = u16::from_be_bytes([bytes[2], bytes[3]]) as usize;
+    let mines = &bytes[MBF_HEADER_LENGTH..];
+    if mines.len() != mines_count * 2 {
+        return Err(LayoutError::Format(format!(
+            "MBF header lists {} mines but has data for {}",
+            mines_count,
+            mines.len() / 2
+        )));
+    }
+
+    let mines: Vec<(u32, u32)> = mines
+        .chunks(2)
+        .map(|mine| (mine[0] as u32, mine[1] as u32))
+        .collect();
+    Board::with_mines(width, height, &mines)
+}
+
+pub fn write_mbf(board: &Board) -> Result<Vec<u8>, LayoutError> {
+    let mines = board.get_mine_positions();
+    if *board.get_width() > u8::MAX as u32
+        || *board.get_height() > u8::MAX as u32
+        || mines.len() > u16::MAX as usize
+    {
+        return Err(LayoutError::Format(
+            "Board is too big for MBF, it holds up to 255x255 cells".to_string(),
+        ));
+    }
+
+    let mut bytes = Vec::with_capacity(MBF_HEADER_LENGTH + mines.len() * 2);
+    bytes.push(*board.get_width() as u8);
+    bytes.push(*board.get_height() as u8);
+    bytes.extend_from_slice(&(mines.len() as u16).to_be_bytes());
+    for (x, y) in mines {
+        bytes.push(x as u8);
+        bytes.push(y as u8);
+    }
+    Ok(bytes)
+}
+
+// Only mines matter, numbers are checked against them and hidden or revealed cells are safe
+pub fn read_text(text: &str) -> Result<Board, LayoutError> {
+    let grid: Board = text
+        .parse()
+        .map_err(|e: ParseError| LayoutError::Format(e.to_string()))?;
+    Board::with_mines(
+        *grid.get_width(),
+        *grid.get_height(),
+        &grid.get_mine_positions(),
+    )
+}
+
+// Numbers are written out and checked against the mines on reading, the wrapped ones of a
+// torus wouldn't match
+pub fn write_text(board: &Board) -> Result<String, LayoutError> {
+    if *board.get_topology() == Topology::Torus {
+        return Err(LayoutError::Format(
+            "Text layouts can't hold a torus, save it as MBF".to_string(),
+        ));
+    }
+    Ok(format!("{:#}", board))
+}
+
+// Format is picked by the extension
+pub fn load(path: &Path) -> Result<Board, LayoutError> {
+    let bytes = fs::read(path).map_err(|e| LayoutError::Io(e.to_string()))?;
+    if is_mbf(path) {
+        read_mbf(&bytes)
+    } else {
+        let text = String::from_utf8(bytes).map_err(|e| LayoutError::Format(e.to_string()))?;
+        read_text(&text)
+    }
+}
+
+pub fn save(board: &Board, path: &Path) -> Result<(), LayoutError> {
+    let bytes = if is_mbf(path) {
+        write_mbf(board)?
+    } else {
+        write_text(board)?.into_bytes()
+    };
+    fs::write(path, bytes).map_err(|e| LayoutError::Io(e.to_string()))
+}
+
+fn is_mbf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("mbf"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINES: [(u32, u32); 3] = [(0, 0), (4, 1), (2, 3)];
+
+    #[test]
+    fn mbf_round_trips() {
+        let board = Board::with_mines(5, 4, &MINES).unwrap();
+        let bytes = write_mbf(&board).unwrap();
+        assert_eq!(&bytes[..MBF_HEADER_LENGTH], &[5, 4, 0, 3]);
+
+        let read = read_mbf(&bytes).unwrap();
+        assert_eq!(read.get_mine_positions(), board.get_mine_positions());
+        assert_eq!(format!("{:#}", read), format!("{:#}", board));
+    }
+
+    #[test]
+    fn text_round_trips() {
+        let board = Board::with_mines(5, 4, &MINES).unwrap();
+        let read = read_text(&write_text(&board).unwrap()).unwrap();
+        assert_eq!(read.get_mine_positions(), board.get_mine_positions());
+    }
+
+    #[test]
+    fn torus_is_not_written_as_text() {
+        let mut board = Board::with_mines(5, 4, &MINES).unwrap();
+        board.set_topology(Topology::Torus);
+        assert!(matches!(write_text(&board), Err(LayoutError::Format(_))));
+    }
+
+    #[test]
+    fn rejects_malformed_mbf() {
+        assert!(matches!(read_mbf(&[5, 4]), Err(LayoutError::Format(_))));
+        assert!(matches!(
+            read_mbf(&[5, 4, 0, 2, 1, 1]),
+            Err(LayoutError::Format(_))
+        ));
+        assert_eq!(
+            read_mbf(&[5, 4, 0, 1, 5, 0]).err(),
+            Some(LayoutError::OutOfBounds { x: 5, y: 0 })
+        );
+        assert_eq!(
+            read_mbf(&[5, 4, 0, 2, 1, 1, 1, 1]).err(),
+            Some(LayoutError::DuplicateMine { x: 1, y: 1 })
+        );
+        assert_eq!(read_mbf(&[0, 4, 0, 0]).err(), Some(LayoutError::Empty));
+    }
+
+    #[test]
+    fn rejects_boards_too_big_for_mbf() {
+        let board = Board::with_mines(256, 1, &[(0, 0)]).unwrap();
+        assert!(matches!(write_mbf(&board), Err(LayoutError::Format(_))));
+    }
+
+    #[test]
+    fn rejects_malformed_text() {
+        assert!(matches!(read_text("*2\n11\n"), Err(LayoutError::Format(_))));
+        assert!(matches!(read_text(""), Err(LayoutError::Format(_))));
+    }
+}
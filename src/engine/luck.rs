@@ -0,0 +1,295 @@
+extern crate rand;
+
+use crate::engine::board::Board;
+use crate::engine::field::*;
+use crate::engine::solver::{self, Constraint, Knowledge, MAX_SEARCH_STEPS};
+
+use std::collections::{HashMap, HashSet};
+
+// New place for every mine with the given cell left safe, agreeing with every number
+// the player has seen. Only frontier cells close to the cell change, mines they gain or
+// lose are taken from or put in cells no number touches. None when the numbers prove
+// the cell is a mine or no layout was found in time.
+pub fn rearrange(board: &Board, safe: usize) -> Option<Vec<bool>> {
+    let fields = board.get_fields_array();
+    let mines: Vec<bool> = fields
+        .iter()
+        .map(|field| field.field_type == FieldType::Mine)
+        .collect();
+
+    // Right flags stay where they are, wrong ones are just hidden cells. The cell itself
+    // may be flagged too, it's about to become safe either way
+    let knowledge: Vec<Knowledge> = fields
+        .iter()
+        .enumerate()
+        .map(
+            |(index, field)| match (field.field_status, field.field_type) {
+                _ if index == safe => Knowledge::Unknown,
+                (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => {
+                    Knowledge::Number(mines_nearby)
+                }
+                (FieldStatus::Revealed, _) => Knowledge::Number(0),
+                (FieldStatus::Unrevealed, FieldType::Mine) if field.is_marked => Knowledge::Mine,
+                (FieldStatus::Unrevealed, _) => Knowledge::Unknown,
+            },
+        )
+        .collect();
+    let constraints = solver::constraints(board, &knowledge);
+
+    let in_frontier: HashSet<usize> = constraints
+        .iter()
+        .flat_map(|constraint| constraint.cells.iter().cloned())
+        .collect();
+    let interior: Vec<usize> = (0..fields.len())
+        .filter(|i| *i != safe && knowledge[*i] == Knowledge::Unknown && !in_frontier.contains(i))
+        .collect();
+
+    // Frontier cells ever further from the cell may change until a layout is found,
+    // the rest of the frontier keeps its mines. A search that runs out of steps doesn't
+    // stop the next, bigger one
+    let origin = board.arr1d_arr2d(safe);
+    let frontier_size = in_frontier.iter().filter(|cell| **cell != safe).count();
+    let mut radius = 1;
+    let (cells, search) = loop {
+        let mut cells: Vec<usize> = in_frontier
+            .iter()
+            .cloned()
+            .filter(|cell| {
+                *cell != safe && board.get_distance(board.arr1d_arr2d(*cell), origin) <= radius
+            })
+            .collect();
+        cells.sort_by_key(|cell| (board.get_distance(board.arr1d_arr2d(*cell), origin), *cell));
+        cells.insert(0, safe);
+
+        if let Some(search) = find_layout(&constraints, &cells, &mines, &interior) {
+            break (cells, search);
+        }
+        if cells.len() - 1 == frontier_size {
+            return None;
+        }
+        radius += 1;
+    };
+
+    let mut layout = mines;
+    for (cell, is_mine) in cells.iter().zip(search.assignment.iter()) {
+        layout[*cell] = *is_mine;
+    }
+
+    // The changed cells' mine count changed, the interior makes up the difference
+    let placed = search.assignment.iter().filter(|m| **m).count() as i64;
+    let mut difference = search.mines_before - placed;
+    while difference != 0 {
+        let candidates: Vec<usize> = interior
+            .iter()
+            .cloned()
+            .filter(|i| layout[*i] == (difference < 0))
+            .collect();
+        let cell = candidates[rand::random::<usize>() % candidates.len()];
+        layout[cell] = difference > 0;
+        difference -= difference.signum();
+    }
+    Some(layout)
+}
+
+fn find_layout(
+    constraints: &[Constraint],
+    cells: &[usize],
+    mines: &[bool],
+    interior: &[usize],
+) -> Option<Rearrangement> {
+    let positions: HashMap<usize, usize> = cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let part: Vec<&Constraint> = constraints
+        .iter()
+        .filter(|constraint| {
+            constraint
+                .cells
+                .iter()
+                .any(|cell| positions.contains_key(cell))
+        })
+        .collect();
+
+    let mut cell_constraints = vec![Vec::new(); cells.len()];
+    let mut targets = Vec::with_capacity(part.len());
+    let mut unassigned = Vec::with_capacity(part.len());
+    for (index, constraint) in part.iter().enumerate() {
+        let mut fixed_mines = 0;
+        let mut free = 0;
+        for cell in constraint.cells.iter() {
+            match positions.get(cell) {
+                Some(position) => {
+                    cell_constraints[*position].push(index);
+                    free += 1;
+                }
+                None if mines[*cell] => fixed_mines += 1,
+                None => {}
+            }
+        }
+        // Mines kept outside the cells already break the number
+        if fixed_mines > constraint.mines {
+            return None;
+        }
+        targets.push(constraint.mines - fixed_mines);
+        unassigned.push(free);
+    }
+
+    let mut search = Rearrangement {
+        current: cells.iter().map(|cell| mines[*cell]).collect(),
+        assignment: vec![false; cells.len()],
+        cell_constraints,
+        placed: vec![0; targets.len()],
+        targets,
+        unassigned,
+        mines_before: cells.iter().filter(|cell| mines[**cell]).count() as i64,
+        interior_free: interior.iter().filter(|i| !mines[**i]).count() as i64,
+        interior_mines: interior.iter().filter(|i| mines[**i]).count() as i64,
+        steps_left: MAX_SEARCH_STEPS,
+    };
+    if search.assign(0) {
+        Some(search)
+    } else {
+        None
+    }
+}
+
+// Backtracking like the solver's, stopping at the first layout that works or after
+// MAX_SEARCH_STEPS cells. Every cell tries its current state first so as little as
+// possible moves
+struct Rearrangement {
+    current: Vec<bool>,
+    assignment: Vec<bool>,
+    cell_constraints: Vec<Vec<usize>>,
+    targets: Vec<u32>,
+    placed: Vec<u32>,
+    unassigned: Vec<u32>,
+    mines_before: i64,
+    interior_free: i64,
+    interior_mines: i64,
+    steps_left: u32,
+}
+
+impl Rearrangement {
+    fn assign(&mut self, position: usize) -> bool {
+        if self.steps_left == 0 {
+            return false;
+        }
+        self.steps_left -= 1;
+
+        if position == self.assignment.len() {
+            let placed = self.assignment.iter().filter(|m| **m).count() as i64;
+            let difference = self.mines_before - placed;
+            return difference <= self.interior_free && -difference <= self.interior_mines;
+        }
+
+        // The first cell is the one that has to stay safe
+        let choices = if position == 0 {
+            vec![false]
+        } else {
+            vec![self.current[position], !self.current[position]]
+        };
+        for is_mine in choices {
+            self.assignment[position] = is_mine;
+            let mut consistent = true;
+            for constraint in self.cell_constraints[position].iter() {
+                self.unassigned[*constraint] -= 1;
+                if is_mine {
+                    self.placed[*constraint] += 1;
+                }
+                let placed = self.placed[*constraint];
+                if placed > self.targets[*constraint]
+                    || placed + self.unassigned[*constraint] < self.targets[*constraint]
+                {
+                    consistent = false;
+                }
+            }
+
+            if consistent && self.assign(position + 1) {
+                return true;
+            }
+
+            for constraint in self.cell_constraints[position].iter() {
+                self.unassigned[*constraint] += 1;
+                if is_mine {
+                    self.placed[*constraint] -= 1;
+                }
+            }
+        }
+        self.assignment[position] = false;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::Action;
+
+    fn keeps_numbers(board: &Board, layout: &[bool]) -> bool {
+        board
+            .get_fields_array()
+            .iter()
+            .enumerate()
+            .all(
+                |(index, field)| match (field.field_status, field.field_type) {
+                    (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => {
+                        let around = board.get_neighbours(index).into_iter();
+                        !layout[index]
+                            && around.filter(|n| layout[*n]).count() == mines_nearby as usize
+                    }
+                    (FieldStatus::Revealed, _) => !layout[index],
+                    _ => true,
+                },
+            )
+    }
+
+    #[test]
+    fn mine_moves_to_the_other_side_of_a_fifty_fifty() {
+        let mut board = Board::with_mines(4, 2, &[(1, 0)]).unwrap();
+        board.click(0, 0, Action::Reveal);
+        board.click(0, 1, Action::Reveal);
+
+        let layout = rearrange(&board, board.arr2d_arr1d(1, 0)).unwrap();
+        let mines: Vec<usize> = (0..layout.len()).filter(|i| layout[*i]).collect();
+        assert_eq!(mines, vec![board.arr2d_arr1d(1, 1)]);
+    }
+
+    #[test]
+    fn rearranged_layouts_keep_what_the_player_saw() {
+        let mines = [
+            (1, 0),
+            (4, 1),
+            (6, 2),
+            (0, 4),
+            (3, 5),
+            (7, 6),
+            (2, 7),
+            (5, 7),
+        ];
+        let mut board = Board::with_mines(8, 8, &mines).unwrap();
+        board.click(7, 0, Action::Reveal);
+        board.click(0, 7, Action::Reveal);
+        board.click(4, 1, Action::Mark);
+
+        let mut rearranged = 0;
+        for safe in 0..board.get_fields_array().len() {
+            let field = board.get_fields_array()[safe];
+            if field.field_type != FieldType::Mine || field.is_marked {
+                continue;
+            }
+            if let Some(layout) = rearrange(&board, safe) {
+                rearranged += 1;
+                assert!(!layout[safe]);
+                assert!(layout[board.arr2d_arr1d(4, 1)]);
+                assert_eq!(layout.iter().filter(|m| **m).count(), mines.len());
+                assert!(keeps_numbers(&board, &layout));
+            }
+        }
+        assert!(rearranged > 0);
+    }
+
+    #[test]
+    fn certain_mine_stays() {
+        let mut board = Board::with_mines(3, 1, &[(0, 0)]).unwrap();
+        board.click(2, 0, Action::Reveal);
+        assert_eq!(rearrange(&board, 0), None);
+    }
+}
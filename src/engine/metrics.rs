@@ -0,0 +1,298 @@
+use crate::engine::board::Board;
+use crate::engine::field::*;
+
+// How hard a mine layout is, the player's progress doesn't matter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Metrics {
+    pub three_bv: u32,
+    // Areas without any numbers, a click clears each of them with its border
+    pub openings: u32,
+    // Groups of touching numbers that no opening reveals
+    pub islands: u32,
+    // Clicks needed when flagging and chording wherever it saves clicks
+    pub zini: u32,
+}
+
+pub fn analyse(board: &Board) -> Metrics {
+    let openings = Openings::find(board);
+    Metrics {
+        three_bv: three_bv(board),
+        openings: openings.cells.len() as u32,
+        islands: islands(board, &openings),
+        zini: zini(board, &openings),
+    }
+}
+
+// Bechtel's Board Benchmark Value: least number of left clicks that clear the board,
+// one per opening and one per number no opening reveals
+pub fn three_bv(board: &Board) -> u32 {
+    let openings = Openings::find(board);
+    openings.cells.len() as u32 + openings.isolated().len() as u32
+}
+
+// Part of the 3BV the player has cleared so far
+pub fn solved_three_bv(board: &Board) -> u32 {
+    let openings = Openings::find(board);
+    let fields = board.get_fields_array();
+    let revealed = |index: &usize| fields[*index].field_status == FieldStatus::Revealed;
+
+    let cleared_openings = openings
+        .cells
+        .iter()
+        .enumerate()
+        .filter(|(opening, cells)| {
+            cells
+                .iter()
+                .any(|cell| openings.opening_of[*cell] == Some(*opening) && revealed(cell))
+        })
+        .count();
+    let cleared_numbers = openings
+        .isolated()
+        .iter()
+        .filter(|index| revealed(index))
+        .count();
+    (cleared_openings + cleared_numbers) as u32
+}
+
+struct Openings {
+    // Every opening's cells, numbers on its border included
+    cells: Vec<Vec<usize>>,
+    // Opening each empty cell belongs to
+    opening_of: Vec<Option<usize>>,
+    // Whether any opening reveals the cell
+    covered: Vec<bool>,
+    mines: Vec<bool>,
+}
+
+impl Openings {
+    fn find(board: &Board) -> Openings {
+        let fields = board.get_fields_array();
+        let mut openings = Openings {
+            cells: Vec::new(),
+            opening_of: vec![None; fields.len()],
+            covered: vec![false; fields.len()],
+            mines: fields
+                .iter()
+                .map(|field| field.field_type == FieldType::Mine)
+                .collect(),
+        };
+
+        for start in 0..fields.len() {
+            if fields[start].field_type != FieldType::Empty || openings.opening_of[start].is_some()
+            {
+                continue;
+            }
+
+            let opening = openings.cells.len();
+            let mut cells = vec![start];
+            let mut in_opening = vec![false; fields.len()];
+            in_opening[start] = true;
+            openings.opening_of[start] = Some(opening);
+
+            let mut next = 0;
+            while next < cells.len() {
+                let index = cells[next];
+                next += 1;
+                if fields[index].field_type != FieldType::Empty {
+                    continue;
+                }
+
+                for neighbour in board.get_neighbours(index) {
+                    if in_opening[neighbour] || openings.mines[neighbour] {
+                        continue;
+                    }
+
+                    in_opening[neighbour] = true;
+                    cells.push(neighbour);
+                    if fields[neighbour].field_type == FieldType::Empty {
+                        openings.opening_of[neighbour] = Some(opening);
+                    }
+                }
+            }
+
+            for cell in cells.iter() {
+                openings.covered[*cell] = true;
+            }
+            openings.cells.push(cells);
+        }
+        openings
+    }
+
+    // Numbers that have to be clicked one by one
+    fn isolated(&self) -> Vec<usize> {
+        (0..self.covered.len())
+            .filter(|index| !self.covered[*index] && !self.mines[*index])
+            .collect()
+    }
+}
+
+fn islands(board: &Board, openings: &Openings) -> u32 {
+    let isolated = openings.isolated();
+    let mut visited = vec![false; openings.covered.len()];
+    let mut islands = 0;
+
+    for start in isolated {
+        if visited[start] {
+            continue;
+        }
+
+        islands += 1;
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(index) = stack.pop() {
+            for neighbour in board.get_neighbours(index) {
+                if !visited[neighbour] && !openings.covered[neighbour] && !openings.mines[neighbour]
+                {
+                    visited[neighbour] = true;
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+    islands
+}
+
+// Greedy ZiNi: keeps chording the number that saves the most clicks, then clicks
+// whatever is left one by one
+fn zini(board: &Board, openings: &Openings) -> u32 {
+    let fields = board.get_fields_array();
+    let mut revealed = vec![false; fields.len()];
+    let mut flagged = vec![false; fields.len()];
+    let mut clicks = 0;
+
+    loop {
+        let mut best: Option<(usize, i32, u32)> = None;
+        for (index, field) in fields.iter().enumerate() {
+            if !matches!(field.field_type, FieldType::Pointer { .. }) {
+                continue;
+            }
+
+            let neighbours = board.get_neighbours(index);
+            let mut units = Vec::new();
+            let mut cost = 1;
+            if !revealed[index] {
+                cost += 1;
+                if !openings.covered[index] {
+                    units.push(Unit::Cell(index));
+                }
+            }
+            for neighbour in neighbours {
+                if openings.mines[neighbour] {
+                    if !flagged[neighbour] {
+                        cost += 1;
+                    }
+                } else if !revealed[neighbour] {
+                    let unit = match openings.opening_of[neighbour] {
+                        Some(opening) => Unit::Opening(opening),
+                        None if !openings.covered[neighbour] => Unit::Cell(neighbour),
+                        None => continue,
+                    };
+                    if !units.contains(&unit) {
+                        units.push(unit);
+                    }
+                }
+            }
+
+            let premium = units.len() as i32 - cost as i32;
+            if premium > 0 && best.is_none_or(|(_, best_premium, _)| premium > best_premium) {
+                best = Some((index, premium, cost));
+            }
+        }
+
+        let (index, _, cost) = match best {
+            Some(best) => best,
+            None => break,
+        };
+        clicks += cost;
+        reveal(openings, index, &mut revealed);
+        for neighbour in board.get_neighbours(index) {
+            if openings.mines[neighbour] {
+                flagged[neighbour] = true;
+            } else {
+                reveal(openings, neighbour, &mut revealed);
+            }
+        }
+    }
+
+    for (opening, cells) in openings.cells.iter().enumerate() {
+        let cleared = cells
+            .iter()
+            .any(|cell| openings.opening_of[*cell] == Some(opening) && revealed[*cell]);
+        if !cleared {
+            clicks += 1;
+        }
+    }
+    clicks
+        + openings
+            .isolated()
+            .into_iter()
+            .filter(|index| !revealed[*index])
+            .count() as u32
+}
+
+// Part of the 3BV a click can take off
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Unit {
+    Opening(usize),
+    Cell(usize),
+}
+
+fn reveal(openings: &Openings, index: usize, revealed: &mut [bool]) {
+    match openings.opening_of[index] {
+        Some(opening) => {
+            for cell in openings.cells[opening].iter() {
+                revealed[*cell] = true;
+            }
+        }
+        None => revealed[index] = true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(layout: &str) -> Metrics {
+        analyse(&layout.parse().unwrap())
+    }
+
+    #[test]
+    fn one_opening_clears_everything() {
+        let expected = Metrics {
+            three_bv: 1,
+            openings: 1,
+            islands: 0,
+            zini: 1,
+        };
+        assert_eq!(metrics("*1 \n11 \n   \n"), expected);
+    }
+
+    #[test]
+    fn numbers_outside_openings_are_islands() {
+        let expected = Metrics {
+            three_bv: 3,
+            openings: 1,
+            islands: 2,
+            zini: 3,
+        };
+        assert_eq!(metrics("*1 1*\n11 11\n"), expected);
+        assert_eq!(three_bv(&"*1 1*\n11 11\n".parse().unwrap()), 3);
+    }
+
+    #[test]
+    fn chording_saves_clicks() {
+        let expected = Metrics {
+            three_bv: 5,
+            openings: 0,
+            islands: 1,
+            zini: 3,
+        };
+        assert_eq!(metrics("1*1\n111\n"), expected);
+    }
+
+    #[test]
+    fn revealed_board_is_solved() {
+        let board: Board = "*1 1*\n11 11\n".parse().unwrap();
+        assert_eq!(solved_three_bv(&board), three_bv(&board));
+    }
+}
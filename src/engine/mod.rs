@@ -0,0 +1,12 @@
+pub mod board;
+pub mod bot;
+pub mod field;
+pub mod hint;
+pub mod layout;
+pub mod luck;
+pub mod metrics;
+pub mod probability;
+pub mod scores;
+pub mod solver;
+pub mod stats;
+pub mod text;
@@ -0,0 +1,300 @@
+use crate::engine::board::Board;
+use crate::engine::field::*;
+use crate::engine::solver::{self, Component, Knowledge};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Probabilities {
+    // Revealed cells get None
+    pub cells: Vec<Option<f64>>,
+    // False when the chances are only estimated, see mine_probabilities
+    pub is_exact: bool,
+}
+
+// Chance of a mine in every cell given what the player sees: arrangements of each frontier
+// part are weighted by the number of ways the remaining mines fit into the cells no number
+// touches. Flagged cells are trusted to be mines. A frontier part of more than
+// MAX_ENUMERATED_CELLS cells isn't enumerated, its cells are counted with the ones no
+// number touches and the result isn't exact.
+pub fn mine_probabilities(board: &Board) -> Probabilities {
+    let knowledge = solver::knowledge(board);
+    let constraints = solver::constraints(board, &knowledge);
+
+    let mut is_exact = true;
+    let components: Vec<Component> = solver::components(&constraints)
+        .into_iter()
+        .filter(|component| {
+            let enumerated = !component.arrangements.is_empty();
+            is_exact &= enumerated;
+            enumerated
+        })
+        .collect();
+
+    let mut in_frontier = vec![false; knowledge.len()];
+    for component in components.iter() {
+        for cell in component.cells.iter() {
+            in_frontier[*cell] = true;
+        }
+    }
+    let interior: Vec<usize> = (0..knowledge.len())
+        .filter(|i| knowledge[*i] == Knowledge::Unknown && !in_frontier[*i])
+        .collect();
+
+    let known_mines = knowledge.iter().filter(|k| **k == Knowledge::Mine).count() as u32;
+    let mines_left = board.get_mines_count().saturating_sub(known_mines) as usize;
+
+    let mut probabilities: Vec<Option<f64>> = knowledge
+        .iter()
+        .map(|k| match k {
+            Knowledge::Mine => Some(1f64),
+            Knowledge::Unknown => Some(0f64),
+            _ => None,
+        })
+        .collect();
+
+    // Ways to put the rest of the mines in the interior, relative to each other
+    let ln_factorials = ln_factorials(interior.len());
+    let interior_ways = |frontier_mines: usize| -> Option<f64> {
+        if frontier_mines > mines_left || mines_left - frontier_mines > interior.len() {
+            return None;
+        }
+        let interior_mines = mines_left - frontier_mines;
+        Some(
+            ln_factorials[interior.len()]
+                - ln_factorials[interior_mines]
+                - ln_factorials[interior.len() - interior_mines],
+        )
+    };
+
+    let distributions: Vec<Vec<f64>> = components.iter().map(distribution).collect();
+    let total = convolve_all(&distributions, None);
+
+    // Logarithms are compared to the largest one so the weights don't overflow
+    let ln_weights: Vec<Option<f64>> = (0..total.len()).map(interior_ways).collect();
+    let ln_max = ln_weights
+        .iter()
+        .zip(total.iter())
+        .filter(|(_, ways)| **ways > 0f64)
+        .filter_map(|(weight, _)| *weight)
+        .fold(f64::MIN, f64::max);
+    let weights: Vec<f64> = ln_weights
+        .iter()
+        .map(|w| w.map_or(0f64, |w| (w - ln_max).exp()))
+        .collect();
+
+    let normalization: f64 = total.iter().zip(weights.iter()).map(|(t, w)| t * w).sum();
+    if normalization <= 0f64 {
+        // Numbers contradict each other, e.g. because of a wrong flag
+        let unknown = knowledge
+            .iter()
+            .filter(|k| **k == Knowledge::Unknown)
+            .count();
+        let uniform = (mines_left as f64 / unknown.max(1) as f64).min(1f64);
+        for (probability, k) in probabilities.iter_mut().zip(knowledge.iter()) {
+            if *k == Knowledge::Unknown {
+                *probability = Some(uniform);
+            }
+        }
+        return Probabilities {
+            cells: probabilities,
+            is_exact: false,
+        };
+    }
+
+    for (index, component) in components.iter().enumerate() {
+        let others = convolve_all(&distributions, Some(index));
+        for (position, cell) in component.cells.iter().enumerate() {
+            let mut mine_weight = 0f64;
+            for arrangements in component.arrangements.iter() {
+                let mines = arrangements.mines as usize;
+                for (other_mines, ways) in others.iter().enumerate() {
+                    mine_weight += arrangements.mine_counts[position] as f64
+                        * ways
+                        * weights[mines + other_mines];
+                }
+            }
+            probabilities[*cell] = Some(mine_weight / normalization);
+        }
+    }
+
+    if !interior.is_empty() {
+        let interior_mines: f64 = total
+            .iter()
+            .enumerate()
+            .map(|(frontier_mines, ways)| {
+                ways * weights[frontier_mines] * mines_left.saturating_sub(frontier_mines) as f64
+            })
+            .sum();
+        let probability = interior_mines / normalization / interior.len() as f64;
+        for cell in interior {
+            probabilities[cell] = Some(probability);
+        }
+    }
+
+    Probabilities {
+        cells: probabilities,
+        is_exact,
+    }
+}
+
+// Hidden, unflagged cell least likely to be a mine
+pub fn safest_cell(probabilities: &[Option<f64>], board: &Board) -> Option<usize> {
+    let fields = board.get_fields_array();
+    probabilities
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            fields[*index].field_status == FieldStatus::Unrevealed && !fields[*index].is_marked
+        })
+        .filter_map(|(index, probability)| probability.map(|p| (index, p)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+// Number of arrangements of the part for every count of mines it can hold
+fn distribution(component: &Component) -> Vec<f64> {
+    let most = component
+        .arrangements
+        .iter()
+        .map(|a| a.mines)
+        .max()
+        .unwrap_or(0);
+    let mut distribution = vec![0f64; most as usize + 1];
+    for arrangements in component.arrangements.iter() {
+        distribution[arrangements.mines as usize] = arrangements.count as f64;
+    }
+    distribution
+}
+
+fn convolve_all(distributions: &[Vec<f64>], skip: Option<usize>) -> Vec<f64> {
+    let mut result = vec![1f64];
+    for (index, distribution) in distributions.iter().enumerate() {
+        if Some(index) == skip {
+            continue;
+        }
+
+        let mut next = vec![0f64; result.len() + distribution.len() - 1];
+        for (i, a) in result.iter().enumerate() {
+            for (j, b) in distribution.iter().enumerate() {
+                next[i + j] += a * b;
+            }
+        }
+        result = next;
+    }
+    result
+}
+
+fn ln_factorials(n: usize) -> Vec<f64> {
+    let mut factorials = Vec::with_capacity(n + 1);
+    factorials.push(0f64);
+    for i in 1..=n {
+        factorials.push(factorials[i - 1] + (i as f64).ln());
+    }
+    factorials
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::text::player_view;
+
+    // Every way to put the mines left in the hidden cells, counting the ones that agree
+    // with all the numbers
+    fn brute_force(board: &Board) -> Vec<Option<f64>> {
+        let knowledge = solver::knowledge(board);
+        let hidden: Vec<usize> = (0..knowledge.len())
+            .filter(|i| knowledge[*i] == Knowledge::Unknown)
+            .collect();
+        let flags = knowledge.iter().filter(|k| **k == Knowledge::Mine).count();
+        let mines_left = *board.get_mines_count() as usize - flags;
+
+        let mut mine_counts = vec![0u64; knowledge.len()];
+        let mut layouts = 0u64;
+        for bits in 0u32..1 << hidden.len() {
+            if bits.count_ones() as usize != mines_left {
+                continue;
+            }
+            let mut is_mine: Vec<bool> = knowledge.iter().map(|k| *k == Knowledge::Mine).collect();
+            for (bit, cell) in hidden.iter().enumerate() {
+                is_mine[*cell] = bits & 1 << bit != 0;
+            }
+            let agrees = knowledge.iter().enumerate().all(|(index, k)| match k {
+                Knowledge::Number(number) => {
+                    let around = board
+                        .get_neighbours(index)
+                        .into_iter()
+                        .filter(|n| is_mine[*n]);
+                    around.count() == *number as usize
+                }
+                _ => true,
+            });
+            if agrees {
+                layouts += 1;
+                for cell in hidden.iter().filter(|cell| is_mine[**cell]) {
+                    mine_counts[*cell] += 1;
+                }
+            }
+        }
+
+        knowledge
+            .iter()
+            .enumerate()
+            .map(|(index, k)| match k {
+                Knowledge::Unknown => Some(mine_counts[index] as f64 / layouts as f64),
+                Knowledge::Mine => Some(1f64),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn agrees_with_brute_force_on_small_boards() {
+        let boards = [
+            ("1...\n1...\n", 2),
+            ("...1\n.4..\n", 4),
+            ("....\n.21.\n....\n", 3),
+            ("....\n.1..\n..2.\n....\n", 4),
+            ("F1..\n11..\n....\n", 3),
+        ];
+        for (text, mines) in boards.iter() {
+            let board = player_view(text, *mines);
+            let probabilities = mine_probabilities(&board);
+            assert!(probabilities.is_exact);
+            for (found, expected) in probabilities.cells.iter().zip(brute_force(&board)) {
+                match (found, expected) {
+                    (Some(found), Some(expected)) => {
+                        assert!((found - expected).abs() < 1e-9, "{}", text)
+                    }
+                    (found, expected) => assert_eq!(*found, expected, "{}", text),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn big_frontier_is_only_estimated() {
+        // Mines at every third cell of the top row, each number sees one of them. The
+        // numbers make up one part too big to enumerate and the bottom row is interior
+        let width = solver::MAX_ENUMERATED_CELLS + 2;
+        let hidden = ".".repeat(width);
+        let text = format!(
+            "{}\n{}\n{}\n{}\n",
+            hidden,
+            "1".repeat(width),
+            hidden,
+            hidden
+        );
+        let mines = width / 3;
+        let probabilities = mine_probabilities(&player_view(&text, mines as u32));
+        assert!(!probabilities.is_exact);
+
+        // Every hidden cell gets the same share of the mines
+        let uniform = mines as f64 / (3 * width) as f64;
+        for (index, probability) in probabilities.cells.iter().enumerate() {
+            if index / width == 1 {
+                assert_eq!(*probability, None);
+            } else {
+                assert!((probability.unwrap() - uniform).abs() < 1e-9);
+            }
+        }
+    }
+}
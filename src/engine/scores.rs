@@ -0,0 +1,81 @@
+use crate::engine::board::{Board, GameStatus};
+use crate::engine::metrics::Metrics;
+use crate::engine::stats::Results;
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+// First line of a new scores file, one line per finished game follows
+pub const HEADER: &str = "status,width,height,mines,time_ms,three_bv,solved_three_bv,clicks,wasted_clicks,three_bv_per_second,efficiency,openings,islands,zini";
+
+// Score of a finished game with the difficulty of its layout, so scores on easy and hard
+// boards can be told apart later
+pub fn line(board: &Board, results: &Results, metrics: &Metrics) -> String {
+    let status = match board.get_status() {
+        GameStatus::Won => "won",
+        GameStatus::Lost => "lost",
+        GameStatus::Playing => "playing",
+    };
+    format!(
+        "{},{},{},{},{},{},{},{},{},{:.3},{:.3},{},{},{}",
+        status,
+        board.get_width(),
+        board.get_height(),
+        board.get_mines_count(),
+        results.time.as_millis(),
+        results.three_bv,
+        results.solved_three_bv,
+        results.clicks(),
+        results.wasted_clicks,
+        results.three_bv_per_second(),
+        results.efficiency(),
+        metrics.openings,
+        metrics.islands,
+        metrics.zini,
+    )
+}
+
+// Adds the line at the end of the file, starting it with the header when it's new
+pub fn append(path: &Path, line: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", HEADER)?;
+    }
+    writeln!(file, "{}", line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::Action;
+    use crate::engine::{metrics, stats};
+
+    #[test]
+    fn line_matches_the_header() {
+        let mut board = Board::with_mines(4, 4, &[(3, 0), (0, 3)]).unwrap();
+        board.click(0, 0, Action::Reveal);
+        board.click(3, 0, Action::Mark);
+        board.click(0, 3, Action::Mark);
+
+        let line = line(&board, &stats::results(&board), &metrics::analyse(&board));
+        let fields: Vec<&str> = line.split(',').collect();
+        assert_eq!(fields.len(), HEADER.split(',').count());
+        // Time and speed depend on how fast the test ran
+        assert_eq!(fields[..4], ["won", "4", "4", "2"]);
+        assert_eq!(fields[5..9], ["1", "1", "3", "0"]);
+        assert_eq!(fields[10..], ["0.333", "1", "0", "1"]);
+    }
+
+    #[test]
+    fn header_is_written_once() {
+        let path = std::env::temp_dir().join(format!("scores-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        append(&path, "first").unwrap();
+        append(&path, "second").unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text, format!("{}\nfirst\nsecond\n", HEADER));
+    }
+}
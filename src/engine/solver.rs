@@ -0,0 +1,842 @@
+use crate::engine::board::{Board, GameStatus};
+use crate::engine::field::*;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// Frontier parts with more unknown cells than this aren't enumerated, it could take ages
+pub const MAX_ENUMERATED_CELLS: usize = 48;
+// Cells a search for one layout may try before it gives up, for parts too big to enumerate
+pub const MAX_SEARCH_STEPS: u32 = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    // A number whose mines are all found, or whose hidden neighbours are all mines
+    SingleCell,
+    // One number's hidden neighbours are a part of another's
+    Subset,
+    // Every arrangement of mines around the numbers agrees
+    Enumeration,
+    // Counting all the mines left, e.g. none left to place or as many as hidden cells
+    MineCount,
+}
+
+// Cell that is certainly safe or certainly a mine, with the numbers that prove it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deduction {
+    pub cell: (i32, i32),
+    pub is_mine: bool,
+    pub rule: Rule,
+    pub sources: Vec<(i32, i32)>,
+}
+
+impl fmt::Display for Deduction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = if self.is_mine { "a mine" } else { "safe" };
+        write!(f, "{},{} is {}: ", self.cell.0, self.cell.1, what)?;
+
+        let sources = self
+            .sources
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<String>>()
+            .join(" and ");
+        match (self.rule, self.is_mine) {
+            (Rule::SingleCell, false) => write!(f, "the number at {} has all its mines", sources),
+            (Rule::SingleCell, true) => write!(
+                f,
+                "the number at {} needs all of its hidden neighbours",
+                sources
+            ),
+            (Rule::Subset, _) => write!(f, "the numbers at {} overlap", sources),
+            (Rule::Enumeration, _) => {
+                write!(f, "every arrangement of mines around {} agrees", sources)
+            }
+            (Rule::MineCount, _) if !self.sources.is_empty() => {
+                write!(f, "the mines left only fit one way around {}", sources)
+            }
+            (Rule::MineCount, false) => write!(f, "all mines are accounted for"),
+            (Rule::MineCount, true) => write!(f, "every hidden cell left is a mine"),
+        }
+    }
+}
+
+// What the player knows about a cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Knowledge {
+    Number(u8),
+    Unknown,
+    Mine,
+    Safe,
+}
+
+// Number's unknown neighbours and how many mines are still hidden among them
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    pub source: usize,
+    pub cells: Vec<usize>,
+    pub mines: u32,
+}
+
+// Arrangements of one frontier part that use the same number of mines
+#[derive(Clone, Debug)]
+pub struct Arrangements {
+    pub mines: u32,
+    pub count: u64,
+    // How many of the arrangements have a mine in each of the part's cells
+    pub mine_counts: Vec<u64>,
+}
+
+// Frontier part whose cells are linked through numbers, enumerated independently of the rest
+#[derive(Clone, Debug)]
+pub struct Component {
+    pub cells: Vec<usize>,
+    pub sources: Vec<usize>,
+    pub arrangements: Vec<Arrangements>,
+}
+
+// Flags are trusted to be right, like a player reasoning about the board would
+pub fn knowledge(board: &Board) -> Vec<Knowledge> {
+    board
+        .get_fields_array()
+        .iter()
+        .map(|field| match (field.field_status, field.field_type) {
+            (FieldStatus::Revealed, FieldType::Empty) => Knowledge::Number(0),
+            (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => {
+                Knowledge::Number(mines_nearby)
+            }
+            (FieldStatus::Revealed, FieldType::Mine) => Knowledge::Mine,
+            (FieldStatus::Unrevealed, _) if field.is_marked => Knowledge::Mine,
+            (FieldStatus::Unrevealed, _) => Knowledge::Unknown,
+        })
+        .collect()
+}
+
+pub fn constraints(board: &Board, knowledge: &[Knowledge]) -> Vec<Constraint> {
+    let mut constraints = Vec::new();
+    for (index, cell) in knowledge.iter().enumerate() {
+        let number = match cell {
+            Knowledge::Number(number) => *number as u32,
+            _ => continue,
+        };
+
+        let mut cells = Vec::new();
+        let mut known_mines = 0;
+        for neighbour in board.get_neighbours(index) {
+            match knowledge[neighbour] {
+                Knowledge::Unknown => cells.push(neighbour),
+                Knowledge::Mine => known_mines += 1,
+                _ => {}
+            }
+        }
+
+        if !cells.is_empty() {
+            cells.sort_unstable();
+            constraints.push(Constraint {
+                source: index,
+                cells,
+                mines: number.saturating_sub(known_mines),
+            });
+        }
+    }
+    constraints
+}
+
+// Splits the frontier into parts that don't share any cells and enumerates each one
+pub fn components(constraints: &[Constraint]) -> Vec<Component> {
+    let mut cell_constraints: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, constraint) in constraints.iter().enumerate() {
+        for cell in constraint.cells.iter() {
+            cell_constraints.entry(*cell).or_default().push(index);
+        }
+    }
+
+    let mut visited = vec![false; constraints.len()];
+    let mut components = Vec::new();
+    for start in 0..constraints.len() {
+        if visited[start] {
+            continue;
+        }
+
+        visited[start] = true;
+        let mut stack = vec![start];
+        let mut part = Vec::new();
+        while let Some(index) = stack.pop() {
+            part.push(index);
+            for cell in constraints[index].cells.iter() {
+                for other in cell_constraints[cell].iter() {
+                    if !visited[*other] {
+                        visited[*other] = true;
+                        stack.push(*other);
+                    }
+                }
+            }
+        }
+
+        let part: Vec<&Constraint> = part.iter().map(|i| &constraints[*i]).collect();
+        // Cells in the order they were reached keep neighbours close, which prunes early
+        let mut seen = HashSet::new();
+        let cells: Vec<usize> = part
+            .iter()
+            .flat_map(|constraint| constraint.cells.iter().cloned())
+            .filter(|cell| seen.insert(*cell))
+            .collect();
+
+        let arrangements = if cells.len() <= MAX_ENUMERATED_CELLS {
+            enumerate(&cells, &part)
+        } else {
+            Vec::new()
+        };
+        components.push(Component {
+            cells,
+            sources: part.iter().map(|constraint| constraint.source).collect(),
+            arrangements,
+        });
+    }
+    components
+}
+
+// Backtracking over the part's cells, dropping branches that break any number
+fn enumerate(cells: &[usize], constraints: &[&Constraint]) -> Vec<Arrangements> {
+    let positions: HashMap<usize, usize> = cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let mut cell_constraints = vec![Vec::new(); cells.len()];
+    for (index, constraint) in constraints.iter().enumerate() {
+        for cell in constraint.cells.iter() {
+            cell_constraints[positions[cell]].push(index);
+        }
+    }
+
+    let mut search = Search {
+        cell_constraints,
+        targets: constraints.iter().map(|c| c.mines).collect(),
+        placed: vec![0; constraints.len()],
+        unassigned: constraints.iter().map(|c| c.cells.len() as u32).collect(),
+        assignment: vec![false; cells.len()],
+        found: HashMap::new(),
+    };
+    search.assign(0);
+
+    let mut arrangements: Vec<Arrangements> = search.found.into_values().collect();
+    arrangements.sort_by_key(|a| a.mines);
+    arrangements
+}
+
+struct Search {
+    cell_constraints: Vec<Vec<usize>>,
+    targets: Vec<u32>,
+    placed: Vec<u32>,
+    unassigned: Vec<u32>,
+    assignment: Vec<bool>,
+    found: HashMap<u32, Arrangements>,
+}
+
+impl Search {
+    fn assign(&mut self, position: usize) {
+        if position == self.assignment.len() {
+            self.record();
+            return;
+        }
+
+        for is_mine in [false, true].iter() {
+            self.assignment[position] = *is_mine;
+            let mut consistent = true;
+            for constraint in self.cell_constraints[position].iter() {
+                self.unassigned[*constraint] -= 1;
+                if *is_mine {
+                    self.placed[*constraint] += 1;
+                }
+                let placed = self.placed[*constraint];
+                if placed > self.targets[*constraint]
+                    || placed + self.unassigned[*constraint] < self.targets[*constraint]
+                {
+                    consistent = false;
+                }
+            }
+
+            if consistent {
+                self.assign(position + 1);
+            }
+
+            for constraint in self.cell_constraints[position].iter() {
+                self.unassigned[*constraint] += 1;
+                if *is_mine {
+                    self.placed[*constraint] -= 1;
+                }
+            }
+        }
+        self.assignment[position] = false;
+    }
+
+    fn record(&mut self) {
+        let mines = self.assignment.iter().filter(|m| **m).count() as u32;
+        let cells = self.assignment.len();
+        let arrangements = self.found.entry(mines).or_insert_with(|| Arrangements {
+            mines,
+            count: 0,
+            mine_counts: vec![0; cells],
+        });
+
+        arrangements.count += 1;
+        for (count, is_mine) in arrangements
+            .mine_counts
+            .iter_mut()
+            .zip(self.assignment.iter())
+        {
+            if *is_mine {
+                *count += 1;
+            }
+        }
+    }
+}
+
+// Everything that can be proven from what the player sees, simple rules are tried first
+pub fn solve(board: &Board) -> Vec<Deduction> {
+    let mut knowledge = knowledge(board);
+    let mut deductions = Vec::new();
+
+    loop {
+        let constraints = constraints(board, &knowledge);
+        let found = single_cell(&constraints);
+        let found = if found.is_empty() {
+            subsets(&constraints)
+        } else {
+            found
+        };
+        let found = if found.is_empty() {
+            let components = components(&constraints);
+            let found = enumeration(&components, &constraints);
+            if found.is_empty() {
+                mine_count(board, &knowledge, &components)
+            } else {
+                found
+            }
+        } else {
+            found
+        };
+        if found.is_empty() {
+            break;
+        }
+
+        for (index, is_mine, rule, sources) in found {
+            if knowledge[index] != Knowledge::Unknown {
+                continue;
+            }
+
+            knowledge[index] = if is_mine {
+                Knowledge::Mine
+            } else {
+                Knowledge::Safe
+            };
+            let cell = board.arr1d_arr2d(index);
+            let sources = sources.iter().map(|s| board.arr1d_arr2d(*s)).collect();
+            deductions.push(Deduction {
+                cell,
+                is_mine,
+                rule,
+                sources,
+            });
+        }
+    }
+
+    deductions
+}
+
+// Cell index, whether it's a mine, the rule and the numbers behind it
+type Found = (usize, bool, Rule, Vec<usize>);
+
+fn single_cell(constraints: &[Constraint]) -> Vec<Found> {
+    let mut found = Vec::new();
+    for constraint in constraints.iter() {
+        let is_mine = if constraint.mines == 0 {
+            false
+        } else if constraint.mines as usize == constraint.cells.len() {
+            true
+        } else {
+            continue;
+        };
+
+        for cell in constraint.cells.iter() {
+            found.push((*cell, is_mine, Rule::SingleCell, vec![constraint.source]));
+        }
+    }
+    found
+}
+
+// When one number's cells are all around another number too, the difference between
+// their mines has to be in the cells only the bigger one sees
+fn subsets(constraints: &[Constraint]) -> Vec<Found> {
+    let mut cell_constraints: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, constraint) in constraints.iter().enumerate() {
+        for cell in constraint.cells.iter() {
+            cell_constraints.entry(*cell).or_default().push(index);
+        }
+    }
+
+    let mut found = Vec::new();
+    for (index, small) in constraints.iter().enumerate() {
+        let overlapping: HashSet<usize> = small
+            .cells
+            .iter()
+            .flat_map(|cell| cell_constraints[cell].iter().cloned())
+            .filter(|other| *other != index)
+            .collect();
+
+        for other in overlapping {
+            let big = &constraints[other];
+            if big.cells.len() <= small.cells.len()
+                || !small
+                    .cells
+                    .iter()
+                    .all(|cell| big.cells.binary_search(cell).is_ok())
+                || big.mines < small.mines
+            {
+                continue;
+            }
+
+            let rest: Vec<usize> = big
+                .cells
+                .iter()
+                .cloned()
+                .filter(|cell| small.cells.binary_search(cell).is_err())
+                .collect();
+            let rest_mines = big.mines - small.mines;
+            let is_mine = if rest_mines == 0 {
+                false
+            } else if rest_mines as usize == rest.len() {
+                true
+            } else {
+                continue;
+            };
+
+            for cell in rest {
+                found.push((cell, is_mine, Rule::Subset, vec![small.source, big.source]));
+            }
+        }
+    }
+    found
+}
+
+fn enumeration(components: &[Component], constraints: &[Constraint]) -> Vec<Found> {
+    let mut found = Vec::new();
+    for component in components {
+        if component.cells.len() > MAX_ENUMERATED_CELLS {
+            let part: Vec<&Constraint> = constraints
+                .iter()
+                .filter(|constraint| component.sources.contains(&constraint.source))
+                .collect();
+            for (cell, is_mine) in search_part(&component.cells, &part) {
+                found.push((cell, is_mine, Rule::Enumeration, component.sources.clone()));
+            }
+            continue;
+        }
+
+        let total: u64 = component.arrangements.iter().map(|a| a.count).sum();
+        if total == 0 {
+            continue;
+        }
+
+        for (position, cell) in component.cells.iter().enumerate() {
+            let mine_count: u64 = component
+                .arrangements
+                .iter()
+                .map(|a| a.mine_counts[position])
+                .sum();
+            if mine_count == 0 || mine_count == total {
+                found.push((
+                    *cell,
+                    mine_count == total,
+                    Rule::Enumeration,
+                    component.sources.clone(),
+                ));
+            }
+        }
+    }
+    found
+}
+
+// Parts too big to enumerate: every cell is tried with the value no layout found so far
+// gave it, a value no layout fits at all is proven wrong. Searches that run out of steps
+// prove nothing
+fn search_part(cells: &[usize], constraints: &[&Constraint]) -> Vec<(usize, bool)> {
+    let positions: HashMap<usize, usize> = cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let mut cell_constraints = vec![Vec::new(); cells.len()];
+    for (index, constraint) in constraints.iter().enumerate() {
+        for cell in constraint.cells.iter() {
+            cell_constraints[positions[cell]].push(index);
+        }
+    }
+
+    let order: Vec<usize> = (0..cells.len()).collect();
+    let layout = match Witness::new(&cell_constraints, constraints).find(&order, None, &[]) {
+        Some(layout) => layout,
+        None => return Vec::new(),
+    };
+    let mut seen_mine = layout.clone();
+    let mut seen_safe: Vec<bool> = layout.iter().map(|is_mine| !is_mine).collect();
+
+    let mut found = Vec::new();
+    for position in 0..cells.len() {
+        if seen_mine[position] && seen_safe[position] {
+            continue;
+        }
+
+        // Cells near the tried one come first, a contradiction shows up sooner
+        let mut order = vec![position];
+        let mut queued = vec![false; cells.len()];
+        queued[position] = true;
+        let mut next = 0;
+        while next < order.len() {
+            for constraint in cell_constraints[order[next]].iter() {
+                for cell in constraints[*constraint].cells.iter() {
+                    if !queued[positions[cell]] {
+                        queued[positions[cell]] = true;
+                        order.push(positions[cell]);
+                    }
+                }
+            }
+            next += 1;
+        }
+
+        let is_mine = !seen_mine[position];
+        let mut witness = Witness::new(&cell_constraints, constraints);
+        match witness.find(&order, Some(is_mine), &layout) {
+            Some(other) => {
+                for (position, is_mine) in other.into_iter().enumerate() {
+                    if is_mine {
+                        seen_mine[position] = true;
+                    } else {
+                        seen_safe[position] = true;
+                    }
+                }
+            }
+            None if witness.steps_left > 0 => found.push((cells[position], !is_mine)),
+            None => {}
+        }
+    }
+    found
+}
+
+// Backtracking like the enumeration's that stops at the first layout fitting every number.
+// Cells try the value they have in an earlier layout first, the first one can be fixed
+struct Witness<'a> {
+    cell_constraints: &'a [Vec<usize>],
+    targets: Vec<u32>,
+    placed: Vec<u32>,
+    unassigned: Vec<u32>,
+    assignment: Vec<bool>,
+    steps_left: u32,
+}
+
+impl<'a> Witness<'a> {
+    fn new(cell_constraints: &'a [Vec<usize>], constraints: &[&Constraint]) -> Witness<'a> {
+        Witness {
+            cell_constraints,
+            targets: constraints.iter().map(|c| c.mines).collect(),
+            placed: vec![0; constraints.len()],
+            unassigned: constraints.iter().map(|c| c.cells.len() as u32).collect(),
+            assignment: vec![false; cell_constraints.len()],
+            steps_left: MAX_SEARCH_STEPS,
+        }
+    }
+
+    fn find(
+        &mut self,
+        order: &[usize],
+        first: Option<bool>,
+        preferred: &[bool],
+    ) -> Option<Vec<bool>> {
+        if self.assign(order, first, preferred, 0) {
+            Some(self.assignment.clone())
+        } else {
+            None
+        }
+    }
+
+    fn assign(
+        &mut self,
+        order: &[usize],
+        first: Option<bool>,
+        preferred: &[bool],
+        depth: usize,
+    ) -> bool {
+        if depth == order.len() {
+            return true;
+        }
+        if self.steps_left == 0 {
+            return false;
+        }
+        self.steps_left -= 1;
+
+        let position = order[depth];
+        let preferred_value = preferred.get(position).cloned().unwrap_or(false);
+        let choices = match first {
+            Some(is_mine) if depth == 0 => vec![is_mine],
+            _ => vec![preferred_value, !preferred_value],
+        };
+        for is_mine in choices {
+            self.assignment[position] = is_mine;
+            let mut consistent = true;
+            for constraint in self.cell_constraints[position].iter() {
+                self.unassigned[*constraint] -= 1;
+                if is_mine {
+                    self.placed[*constraint] += 1;
+                }
+                let placed = self.placed[*constraint];
+                if placed > self.targets[*constraint]
+                    || placed + self.unassigned[*constraint] < self.targets[*constraint]
+                {
+                    consistent = false;
+                }
+            }
+
+            if consistent && self.assign(order, first, preferred, depth + 1) {
+                return true;
+            }
+
+            for constraint in self.cell_constraints[position].iter() {
+                self.unassigned[*constraint] += 1;
+                if is_mine {
+                    self.placed[*constraint] -= 1;
+                }
+            }
+        }
+        false
+    }
+}
+
+// The total number of mines has to fit too: frontier arrangements that leave too many or
+// too few mines for the cells away from the numbers are ruled out
+fn mine_count(board: &Board, knowledge: &[Knowledge], components: &[Component]) -> Vec<Found> {
+    let known_mines = knowledge.iter().filter(|k| **k == Knowledge::Mine).count() as u32;
+    let unknown: Vec<usize> = knowledge
+        .iter()
+        .enumerate()
+        .filter(|(_, k)| **k == Knowledge::Unknown)
+        .map(|(index, _)| index)
+        .collect();
+    // Wrong flags can make it look like there are more mines than the board has
+    if known_mines > *board.get_mines_count() {
+        return Vec::new();
+    }
+    let mines_left = board.get_mines_count() - known_mines;
+
+    if mines_left == 0 || mines_left as usize == unknown.len() {
+        return unknown
+            .into_iter()
+            .map(|cell| (cell, mines_left > 0, Rule::MineCount, Vec::new()))
+            .collect();
+    }
+    // Parts too big to enumerate could hold any number of mines
+    if components
+        .iter()
+        .any(|component| component.arrangements.is_empty())
+    {
+        return Vec::new();
+    }
+
+    let in_frontier: HashSet<usize> = components
+        .iter()
+        .flat_map(|component| component.cells.iter().cloned())
+        .collect();
+    let interior: Vec<usize> = unknown
+        .into_iter()
+        .filter(|cell| !in_frontier.contains(cell))
+        .collect();
+    let mines_left = mines_left as usize;
+    // Whether the other parts and the interior can take the rest when a part has this many
+    let fits = |others: &[bool], mines: usize| {
+        (0..others.len()).any(|total| {
+            others[total]
+                && total + mines <= mines_left
+                && mines_left - total - mines <= interior.len()
+        })
+    };
+
+    let mut found = Vec::new();
+    for (index, component) in components.iter().enumerate() {
+        let others = mine_totals(components, Some(index), mines_left);
+        let possible: Vec<&Arrangements> = component
+            .arrangements
+            .iter()
+            .filter(|arrangements| fits(&others, arrangements.mines as usize))
+            .collect();
+        let total: u64 = possible.iter().map(|a| a.count).sum();
+        // The numbers contradict each other or the count, nothing can be trusted
+        if total == 0 {
+            return Vec::new();
+        }
+
+        for (position, cell) in component.cells.iter().enumerate() {
+            let mine_count: u64 = possible.iter().map(|a| a.mine_counts[position]).sum();
+            if mine_count == 0 || mine_count == total {
+                found.push((
+                    *cell,
+                    mine_count == total,
+                    Rule::MineCount,
+                    component.sources.clone(),
+                ));
+            }
+        }
+    }
+
+    if !interior.is_empty() {
+        let frontier = mine_totals(components, None, mines_left);
+        let rest: Vec<usize> = (0..frontier.len())
+            .filter(|total| frontier[*total] && mines_left - total <= interior.len())
+            .map(|total| mines_left - total)
+            .collect();
+        let is_mine = if rest.iter().all(|mines| *mines == 0) {
+            false
+        } else if rest.iter().all(|mines| *mines == interior.len()) {
+            true
+        } else {
+            return found;
+        };
+        for cell in interior {
+            found.push((cell, is_mine, Rule::MineCount, Vec::new()));
+        }
+    }
+    found
+}
+
+// Which totals of mines, up to the limit, the parts can hold together
+fn mine_totals(components: &[Component], skip: Option<usize>, limit: usize) -> Vec<bool> {
+    let mut totals = vec![false; limit + 1];
+    totals[0] = true;
+    for (index, component) in components.iter().enumerate() {
+        if Some(index) == skip {
+            continue;
+        }
+
+        let mut next = vec![false; limit + 1];
+        for total in (0..=limit).filter(|total| totals[*total]) {
+            for arrangements in component.arrangements.iter() {
+                let mines = total + arrangements.mines as usize;
+                if mines <= limit {
+                    next[mines] = true;
+                }
+            }
+        }
+        totals = next;
+    }
+    totals
+}
+
+// Nothing hidden is provably safe, yet some hidden cell isn't a mine, so the player
+// has to guess. Components too large to enumerate can hide safe cells this misses
+pub fn is_guess_forced(board: &Board) -> bool {
+    if *board.get_status() != GameStatus::Playing {
+        return false;
+    }
+
+    let deductions = solve(board);
+    if deductions.iter().any(|deduction| !deduction.is_mine) {
+        return false;
+    }
+    let unknown = knowledge(board)
+        .iter()
+        .filter(|knowledge| **knowledge == Knowledge::Unknown)
+        .count();
+    unknown > deductions.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::text::player_view;
+
+    fn found(board: &Board, cell: (i32, i32)) -> Option<(bool, Rule)> {
+        solve(board)
+            .into_iter()
+            .find(|deduction| deduction.cell == cell)
+            .map(|deduction| (deduction.is_mine, deduction.rule))
+    }
+
+    #[test]
+    fn single_cell_finds_safe_cells_and_mines() {
+        assert_eq!(
+            found(&player_view("F1.\n", 1), (2, 0)),
+            Some((false, Rule::SingleCell))
+        );
+        assert_eq!(
+            found(&player_view(".1 \n", 1), (0, 0)),
+            Some((true, Rule::SingleCell))
+        );
+    }
+
+    #[test]
+    fn subset_finds_what_single_cells_miss() {
+        let board = player_view("...\n111\n", 1);
+        assert_eq!(solve(&board)[0].rule, Rule::Subset);
+        assert_eq!(found(&board, (0, 0)), Some((false, Rule::Subset)));
+        assert_eq!(found(&board, (2, 0)), Some((false, Rule::Subset)));
+        assert_eq!(found(&board, (1, 0)), Some((true, Rule::SingleCell)));
+    }
+
+    #[test]
+    fn enumeration_finds_what_subsets_miss() {
+        let board = player_view("...1\n.4..\n", 4);
+        assert_eq!(solve(&board)[0].rule, Rule::Enumeration);
+        assert_eq!(found(&board, (3, 1)), Some((false, Rule::Enumeration)));
+        for cell in [(0, 0), (1, 0), (0, 1)].iter() {
+            assert_eq!(found(&board, *cell), Some((true, Rule::Enumeration)));
+        }
+    }
+
+    #[test]
+    fn big_parts_are_searched_instead() {
+        // The enumeration test's numbers around cells 0 to 5, then a chain of numbers
+        // that each see two cells and make the part too big to enumerate
+        let constraint = |source: usize, cells: Vec<usize>, mines: u32| Constraint {
+            source,
+            cells,
+            mines,
+        };
+        let mut constraints = vec![
+            constraint(100, vec![0, 1, 2, 3, 4], 4),
+            constraint(101, vec![2, 4, 5], 1),
+        ];
+        for cell in 5..MAX_ENUMERATED_CELLS + 10 {
+            constraints.push(constraint(200 + cell, vec![cell, cell + 1], 1));
+        }
+
+        let components = components(&constraints);
+        assert!(components[0].arrangements.is_empty());
+        let found: Vec<(usize, bool)> = enumeration(&components, &constraints)
+            .into_iter()
+            .map(|(cell, is_mine, _, _)| (cell, is_mine))
+            .collect();
+        for expected in [(0, true), (1, true), (3, true), (5, false), (6, true)].iter() {
+            assert!(found.contains(expected));
+        }
+        assert!(!found.iter().any(|(cell, _)| *cell == 2 || *cell == 4));
+    }
+
+    #[test]
+    fn nothing_is_found_without_numbers() {
+        assert!(solve(&player_view("..\n..\n", 2)).is_empty());
+    }
+
+    // One mine somewhere in the second column and more cells no number touches
+    const FIFTY_FIFTY: &str = "1...\n1...\n";
+
+    #[test]
+    fn mine_count_clears_the_interior() {
+        let board = player_view(FIFTY_FIFTY, 1);
+        assert_eq!(found(&board, (3, 0)), Some((false, Rule::MineCount)));
+        assert_eq!(found(&board, (1, 0)), None);
+        assert!(!is_guess_forced(&board));
+    }
+
+    #[test]
+    fn mine_count_fills_the_interior() {
+        let board = player_view(FIFTY_FIFTY, 5);
+        assert_eq!(found(&board, (2, 1)), Some((true, Rule::MineCount)));
+        assert!(is_guess_forced(&board));
+    }
+
+    #[test]
+    fn guess_is_forced_when_the_count_leaves_a_choice() {
+        assert!(is_guess_forced(&player_view(FIFTY_FIFTY, 3)));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::engine::board::{Action, Board, ClickKind, GameStatus};
+use crate::engine::metrics;
+
+use std::time::Duration;
+
+// How well a finished game was played, from the board's click log
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Results {
+    pub time: Duration,
+    pub three_bv: u32,
+    // Equal to three_bv unless the game was lost
+    pub solved_three_bv: u32,
+    pub left_clicks: u32,
+    pub right_clicks: u32,
+    pub chords: u32,
+    // Clicks that didn't change the board
+    pub wasted_clicks: u32,
+}
+
+impl Results {
+    pub fn clicks(&self) -> u32 {
+        self.left_clicks + self.right_clicks + self.chords
+    }
+
+    pub fn three_bv_per_second(&self) -> f64 {
+        let seconds = self.time.as_secs_f64();
+        if seconds > 0f64 {
+            self.solved_three_bv as f64 / seconds
+        } else {
+            0f64
+        }
+    }
+
+    // 3BV per click, above 1 only with chording
+    pub fn efficiency(&self) -> f64 {
+        if self.clicks() > 0 {
+            self.solved_three_bv as f64 / self.clicks() as f64
+        } else {
+            0f64
+        }
+    }
+
+    // Time the whole board would have taken at the same pace, for lost games
+    pub fn estimated_time(&self) -> Option<Duration> {
+        if self.solved_three_bv == 0 || self.solved_three_bv >= self.three_bv {
+            return None;
+        }
+        Some(
+            self.time
+                .mul_f64(self.three_bv as f64 / self.solved_three_bv as f64),
+        )
+    }
+}
+
+// Clicks of a game by what they achieved
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClickTally {
+    pub useful_reveals: u32,
+    pub wasted: u32,
+    pub flags: u32,
+    pub unflags: u32,
+    pub chords: u32,
+    pub failed_chords: u32,
+}
+
+impl ClickTally {
+    pub fn add(&mut self, kind: ClickKind) {
+        match kind {
+            ClickKind::UsefulReveal => self.useful_reveals += 1,
+            ClickKind::Wasted => self.wasted += 1,
+            ClickKind::Flag => self.flags += 1,
+            ClickKind::Unflag => self.unflags += 1,
+            ClickKind::Chord => self.chords += 1,
+            ClickKind::FailedChord => self.failed_chords += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.useful_reveals
+            + self.wasted
+            + self.flags
+            + self.unflags
+            + self.chords
+            + self.failed_chords
+    }
+}
+
+pub fn tally(board: &Board) -> ClickTally {
+    let mut tally = ClickTally::default();
+    for click in board.get_click_log() {
+        tally.add(click.kind);
+    }
+    tally
+}
+
+pub fn results(board: &Board) -> Results {
+    let three_bv = metrics::three_bv(board);
+    let mut results = Results {
+        time: board.get_elapsed_time(),
+        three_bv,
+        solved_three_bv: match board.get_status() {
+            GameStatus::Won => three_bv,
+            _ => metrics::solved_three_bv(board),
+        },
+        left_clicks: 0,
+        right_clicks: 0,
+        chords: 0,
+        wasted_clicks: 0,
+    };
+
+    for click in board.get_click_log() {
+        match click.action {
+            Action::Reveal => results.left_clicks += 1,
+            Action::Mark => results.right_clicks += 1,
+            Action::Chord => results.chords += 1,
+        }
+    }
+    let tally = tally(board);
+    results.wasted_clicks = tally.wasted + tally.failed_chords;
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::board::ClickOutcome;
+
+    fn results(time: u64, three_bv: u32, solved_three_bv: u32, clicks: u32) -> Results {
+        Results {
+            time: Duration::from_secs(time),
+            three_bv,
+            solved_three_bv,
+            left_clicks: clicks,
+            right_clicks: 0,
+            chords: 0,
+            wasted_clicks: 0,
+        }
+    }
+
+    #[test]
+    fn won_game_counts_its_clicks() {
+        // One opening clears the whole board, the mines are flagged after it
+        let mut board = Board::with_mines(4, 4, &[(3, 0), (0, 3)]).unwrap();
+        assert_eq!(board.click(0, 0, Action::Reveal), ClickOutcome::Cascade);
+        assert_eq!(board.click(0, 0, Action::Reveal), ClickOutcome::Nothing);
+        assert_eq!(board.click(3, 0, Action::Mark), ClickOutcome::Flag);
+        assert_eq!(board.click(0, 3, Action::Mark), ClickOutcome::Won);
+
+        let results = super::results(&board);
+        assert_eq!(results.three_bv, 1);
+        assert_eq!(results.solved_three_bv, 1);
+        assert_eq!(
+            (results.left_clicks, results.chords, results.right_clicks),
+            (1, 1, 2)
+        );
+        assert_eq!(results.wasted_clicks, 1);
+        assert_eq!(results.clicks(), 4);
+        assert_eq!(results.efficiency(), 0.25);
+        assert_eq!(results.estimated_time(), None);
+    }
+
+    #[test]
+    fn tally_counts_every_kind_of_click() {
+        let mut board = Board::with_mines(6, 3, &[(3, 0), (3, 2), (5, 1)]).unwrap();
+        board.click(0, 0, Action::Reveal);
+        board.click(0, 0, Action::Reveal);
+        board.click(3, 1, Action::Reveal);
+        board.click(3, 1, Action::Chord);
+        board.click(3, 0, Action::Mark);
+        board.click(3, 0, Action::Mark);
+        board.click(3, 0, Action::Mark);
+        board.click(3, 2, Action::Mark);
+        board.click(3, 1, Action::Chord);
+        board.click(3, 1, Action::Chord);
+
+        assert_eq!(
+            tally(&board),
+            ClickTally {
+                useful_reveals: 2,
+                wasted: 2,
+                flags: 3,
+                unflags: 1,
+                chords: 1,
+                failed_chords: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn speed_is_solved_three_bv_per_second() {
+        assert_eq!(results(10, 30, 30, 40).three_bv_per_second(), 3.0);
+        assert_eq!(results(10, 30, 10, 40).three_bv_per_second(), 1.0);
+        assert_eq!(results(0, 30, 30, 40).three_bv_per_second(), 0.0);
+    }
+
+    #[test]
+    fn efficiency_is_solved_three_bv_per_click() {
+        assert_eq!(results(10, 30, 30, 20).efficiency(), 1.5);
+        assert_eq!(results(10, 30, 0, 0).efficiency(), 0.0);
+    }
+
+    #[test]
+    fn lost_game_estimates_the_time_at_the_same_pace() {
+        assert_eq!(
+            results(10, 30, 10, 20).estimated_time(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(results(10, 30, 0, 5).estimated_time(), None);
+        assert_eq!(results(10, 30, 30, 20).estimated_time(), None);
+    }
+}
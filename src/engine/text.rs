@@ -0,0 +1,225 @@
+use crate::engine::board::Board;
+use crate::engine::field::*;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// Plain-text boards, one character per cell and one line per row:
+// `.` hidden, `F` flagged, `*` mine, `1`-`8` pointer, space (or `0`) revealed empty.
+// `{}` prints what the player sees, `{:#}` the full layout with everything revealed.
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    UnknownCharacter { character: char, x: u32, y: u32 },
+    // Pointer whose neighbours are all known doesn't match the mines around it
+    WrongCount { x: u32, y: u32 },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Board has no cells"),
+            ParseError::UnknownCharacter { character, x, y } => {
+                write!(f, "Unknown character {:?} at {},{}", character, x, y)
+            }
+            ParseError::WrongCount { x, y } => {
+                write!(f, "Number at {},{} doesn't match the mines around it", x, y)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let glyph = match (self.field_status, self.field_type) {
+            (FieldStatus::Unrevealed, _) if self.is_marked => 'F',
+            (FieldStatus::Unrevealed, _) => '.',
+            (FieldStatus::Revealed, FieldType::Empty) => ' ',
+            (FieldStatus::Revealed, FieldType::Mine) => '*',
+            (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => {
+                (b'0' + mines_nearby) as char
+            }
+        };
+        // Padded so frontends can widen cells with the usual width flags
+        let mut buffer = [0u8; 4];
+        f.pad(glyph.encode_utf8(&mut buffer))
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.get_fields_array().chunks(*self.get_width() as usize) {
+            for field in row {
+                if f.alternate() {
+                    let revealed = Field {
+                        field_status: FieldStatus::Revealed,
+                        is_marked: false,
+                        ..*field
+                    };
+                    write!(f, "{}", revealed)?;
+                } else {
+                    write!(f, "{}", field)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// Hidden cells parse as empty, only a full layout tells where all the mines are.
+// Rows shorter than the longest one are padded with revealed empty cells, editors like
+// to strip trailing spaces.
+impl FromStr for Board {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Board, ParseError> {
+        let mut rows: Vec<&str> = text.lines().collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0) as u32;
+        let height = rows.len() as u32;
+        if width == 0 {
+            return Err(ParseError::Empty);
+        }
+
+        let mut fields = Vec::with_capacity(width as usize * height as usize);
+        for (y, row) in rows.iter().enumerate() {
+            let mut characters = row.chars();
+            for x in 0..width {
+                let (field_status, field_type, is_marked) = match characters.next() {
+                    Some('.') => (FieldStatus::Unrevealed, FieldType::Empty, false),
+                    Some('F') => (FieldStatus::Unrevealed, FieldType::Empty, true),
+                    Some('*') => (FieldStatus::Revealed, FieldType::Mine, false),
+                    Some(' ') | Some('0') | None => {
+                        (FieldStatus::Revealed, FieldType::Empty, false)
+                    }
+                    Some(digit @ '1'..='8') => (
+                        FieldStatus::Revealed,
+                        FieldType::Pointer {
+                            mines_nearby: digit as u8 - b'0',
+                        },
+                        false,
+                    ),
+                    Some(character) => {
+                        return Err(ParseError::UnknownCharacter {
+                            character,
+                            x,
+                            y: y as u32,
+                        })
+                    }
+                };
+                fields.push(Field {
+                    field_status,
+                    field_type,
+                    is_marked,
+                });
+            }
+        }
+
+        let board = Board::from_fields(width, height, fields);
+        check_counts(&board)?;
+        Ok(board)
+    }
+}
+
+// Numbers surrounded only by known cells have to agree with the mines next to them
+fn check_counts(board: &Board) -> Result<(), ParseError> {
+    let fields = board.get_fields_array();
+    for (index, field) in fields.iter().enumerate() {
+        let expected = match (field.field_status, field.field_type) {
+            (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) => mines_nearby,
+            (FieldStatus::Revealed, FieldType::Empty) => 0,
+            _ => continue,
+        };
+
+        let (x, y) = board.arr1d_arr2d(index);
+        let mut mines = 0;
+        let mut hidden = false;
+        for neighbour in board.get_neighbours(index) {
+            match fields[neighbour] {
+                Field {
+                    field_status: FieldStatus::Unrevealed,
+                    ..
+                } => hidden = true,
+                Field {
+                    field_type: FieldType::Mine,
+                    ..
+                } => mines += 1,
+                _ => {}
+            }
+        }
+
+        if mines > expected || (!hidden && mines != expected) {
+            return Err(ParseError::WrongCount {
+                x: x as u32,
+                y: y as u32,
+            });
+        }
+    }
+    Ok(())
+}
+
+// Board as the player sees it with the mine counter at the given count, the text alone
+// only knows about mines that went off
+#[cfg(test)]
+pub(crate) fn player_view(text: &str, mines: u32) -> Board {
+    let mut board: Board = text.parse().unwrap();
+    board.set_mines_count(mines);
+    board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: &str = "*1 \n11 \n   \n";
+
+    #[test]
+    fn full_layout_round_trips() {
+        let board: Board = LAYOUT.parse().unwrap();
+        assert_eq!(format!("{:#}", board), LAYOUT);
+        assert_eq!(*board.get_mines_count(), 1);
+    }
+
+    #[test]
+    fn player_view_round_trips() {
+        let text = "F1 \n.1 \nF.1\n";
+        let board: Board = text.parse().unwrap();
+        assert_eq!(board.to_string(), text);
+    }
+
+    #[test]
+    fn short_rows_are_padded() {
+        let board: Board = "*1\n11 \n".parse().unwrap();
+        assert_eq!(*board.get_width(), 3);
+        assert_eq!(board.to_string(), "*1 \n11 \n");
+    }
+
+    #[test]
+    fn rejects_malformed_text() {
+        assert_eq!("".parse::<Board>().err(), Some(ParseError::Empty));
+        assert_eq!(
+            "*1\n1x\n".parse::<Board>().err(),
+            Some(ParseError::UnknownCharacter {
+                character: 'x',
+                x: 1,
+                y: 1
+            })
+        );
+        assert_eq!(
+            "*2\n11\n".parse::<Board>().err(),
+            Some(ParseError::WrongCount { x: 1, y: 0 })
+        );
+    }
+}
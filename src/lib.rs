@@ -0,0 +1,3 @@
+extern crate rand;
+
+pub mod engine;
@@ -1,24 +1,67 @@
 extern crate sdl2;
 
-use sdl2::event::Event;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 mod mods;
-use mods::field::*;
-use mods::game::*;
-use mods::game_canvas::GameCanvas;
+use minesweeper::engine::board::{Board, ClickOutcome, Topology};
+use minesweeper::engine::bot::Bot;
+use minesweeper::engine::layout;
+use minesweeper::engine::scores;
+use mods::assets::{find_asset, load_font, SOUNDS_DIRECTORY};
+use mods::audio::{Audio, Sound};
+use mods::camera::DRAG_THRESHOLD;
+use mods::frame_counter::FrameCounter;
+use mods::game::Game;
+use mods::error::GameError;
+use mods::options::{Options, USAGE};
+use mods::texture_cache::TextureCache;
 
 const GAME_PARAMS : (u32, u32, u32, u32, (f32, f32), u32) = (800, 600, 20, 15, (1f32 / 4f32, 1f32 / 4f32), 50);
+// Pixels moved per arrow key press
+const PAN_STEP : i32 = 32;
+// Frame cadence while something animates, vsync keeps it in step with the display
+const FRAME_TIME_MS : u64 = 16;
+// Longest sleep while idle, often enough to keep the timer ticking
+const IDLE_TIMEOUT_MS : u64 = 100;
 
 pub fn main() {
-    let sdl_context = sdl2::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
-    let font_context = sdl2::ttf::init().unwrap();
+    if let Err(error) = run() {
+        eprintln!("{}", error);
+        if let GameError::Arguments(_) = error {
+            eprintln!("{}", USAGE);
+        }
+        std::process::exit(error.exit_code());
+    }
+}
+
+fn run() -> Result<(), GameError> {
+    let options = Options::from_args()?;
 
-    let mut font = font_context.load_font("./PxPlus_AmstradPC1512.ttf", 128).unwrap();
+    let sdl_context = sdl2::init().map_err(GameError::Sdl)?;
+    let video_subsystem = sdl_context.video().map_err(GameError::Sdl)?;
+    let font_context = sdl2::ttf::init().map_err(|e| GameError::Sdl(e.to_string()))?;
+    let sounds_directory =
+        find_asset(SOUNDS_DIRECTORY).unwrap_or_else(|| PathBuf::from(SOUNDS_DIRECTORY));
+    let mut audio = Audio::new(sdl_context.audio(), &sounds_directory);
+    audio.set_muted(options.muted);
+
+    let mut font = load_font(&font_context, options.font.as_deref())?;
     font.set_style(sdl2::ttf::FontStyle::NORMAL);
-    
-    let mut game = Game::from_params(GAME_PARAMS);
+
+    // Restarting replays the same layout
+    let layout = match options.layout.as_ref() {
+        Some(path) => Some(
+            layout::load(path)
+                .map_err(|e| GameError::Layout(format!("{}: {}", path.display(), e)))?,
+        ),
+        None => None,
+    };
+
+    let mut game = new_game(layout.as_ref(), &options, options.animations);
 
     let window = video_subsystem
         .window(
@@ -27,46 +70,237 @@ pub fn main() {
             *game.get_window_height(),
         )
         .position_centered()
+        .resizable()
         .build()
-        .unwrap();
+        .map_err(|e| GameError::Window(e.to_string()))?;
 
-    let mut canvas = window.into_canvas().build().unwrap();
+    let mut canvas = window
+        .into_canvas()
+        .present_vsync()
+        .build()
+        .map_err(|e| GameError::Canvas(e.to_string()))?;
     let texture_creator = canvas.texture_creator();
+    let mut texture_cache = TextureCache::new(&font, &texture_creator);
+    texture_cache.set_enabled(options.texture_cache);
 
     canvas.set_draw_color(Color::RGB(0, 0, 0));
     canvas.clear();
     canvas.present();
 
-    let mut event_pump = sdl_context.event_pump().unwrap();
+    let mut event_pump = sdl_context.event_pump().map_err(GameError::Sdl)?;
+
+    let mut mouse_position = (0, 0);
+    // Distance travelled since a button was pressed, None when no button is held
+    let mut drag_distance: Option<i32> = None;
+
+    let mut frame_counter = FrameCounter::new();
+    // FPS overlay, toggled with F, keeps frames coming at the display's refresh rate
+    let mut show_fps = false;
+    let mut redraw = true;
+    let mut next_frame_time = Instant::now();
 
-    game.render(&mut canvas, &font, &texture_creator);
+    // Bot playing the game, started and stopped with B
+    let mut bot: Option<Bot> = None;
+    let bot_move_time = Duration::from_millis(1000 / options.bot_speed as u64);
+    let mut next_bot_move_time = Instant::now();
 
     'running: loop {
-        for event in event_pump.poll_iter() {
+        // Sleeps until an event comes or it's time for the next frame
+        let mut timeout = if show_fps || game.needs_frame() {
+            next_frame_time.saturating_duration_since(Instant::now())
+        } else {
+            Duration::from_millis(IDLE_TIMEOUT_MS)
+        };
+        if bot.is_some() {
+            timeout = timeout.min(next_bot_move_time.saturating_duration_since(Instant::now()));
+        }
+        let first_event = event_pump.wait_event_timeout(timeout.as_millis() as u32);
+
+        for event in first_event.into_iter().chain(event_pump.poll_iter()) {
             match event {
+                Event::MouseButtonDown { .. } => {
+                    drag_distance = Some(0);
+                }
+                Event::MouseMotion {
+                    x, y, xrel, yrel, ..
+                } => {
+                    mouse_position = (x, y);
+                    if let Some(distance) = drag_distance.as_mut() {
+                        *distance += xrel.abs() + yrel.abs();
+                        if *distance > DRAG_THRESHOLD {
+                            game.pan(xrel, yrel);
+                            redraw = true;
+                        }
+                    }
+                }
                 Event::MouseButtonUp {
                     mouse_btn, x, y, ..
                 } => {
-                    game.click(x, y, mouse_btn, &mut canvas, &font, &texture_creator);
+                    let dragged = drag_distance.take().is_some_and(|d| d > DRAG_THRESHOLD);
+                    if !dragged {
+                        let outcome = game.click(x, y, mouse_btn);
+                        save_score(&mut game, &options, outcome);
+                        if let Some(sound) = Sound::for_click(outcome) {
+                            audio.play(sound);
+                        }
+                        redraw = true;
+                    }
                 }
-                Event::Window { .. } => {
-                    game.render(&mut canvas, &font, &texture_creator);
+                Event::MouseWheel { y, .. } => {
+                    game.zoom(y, mouse_position.0, mouse_position.1);
+                    redraw = true;
                 }
-                Event::KeyUp {keycode, ..} => {
-                    if let Some(k) = keycode {
-                        if k == sdl2::keyboard::Keycode::Q {
-                            game.set_all_visible();
-                        } else if k == sdl2::keyboard::Keycode::R {
-                            game = Game::from_params(GAME_PARAMS);
-                            game.render(&mut canvas, &font, &texture_creator);
+                Event::KeyDown {
+                    keycode: Some(k), ..
+                } => {
+                    let (width, height) = canvas.window().size();
+                    match k {
+                        Keycode::Left => game.pan(PAN_STEP, 0),
+                        Keycode::Right => game.pan(-PAN_STEP, 0),
+                        Keycode::Up => game.pan(0, PAN_STEP),
+                        Keycode::Down => game.pan(0, -PAN_STEP),
+                        Keycode::Equals | Keycode::KpPlus => {
+                            game.zoom(1, width as i32 / 2, height as i32 / 2)
+                        }
+                        Keycode::Minus | Keycode::KpMinus => {
+                            game.zoom(-1, width as i32 / 2, height as i32 / 2)
                         }
+                        Keycode::Num0 => game.reset_camera(),
+                        _ => continue,
+                    }
+                    redraw = true;
+                }
+                Event::Window { win_event, .. } => {
+                    if let WindowEvent::SizeChanged(width, height) = win_event {
+                        game.resize(width as u32, height as u32);
+                        texture_cache.clear();
                     }
+                    game.request_full_redraw();
+                    redraw = true;
                 }
+                Event::KeyUp {
+                    keycode: Some(k), ..
+                } => match k {
+                    Keycode::Q => {
+                        game.set_all_visible();
+                        redraw = true;
+                    }
+                    Keycode::R => {
+                        let (width, height) = canvas.window().size();
+                        let animations = game.is_animations_enabled();
+                        game = new_game(layout.as_ref(), &options, animations);
+                        bot = None;
+                        game.resize(width, height);
+                        redraw = true;
+                    }
+                    // Animations can be skipped one by one or turned off altogether
+                    Keycode::Space | Keycode::Escape => {
+                        game.skip_animations();
+                        redraw = true;
+                    }
+                    Keycode::A => {
+                        game.set_animations_enabled(!game.is_animations_enabled());
+                        redraw = true;
+                    }
+                    Keycode::M => audio.set_muted(!audio.is_muted()),
+                    Keycode::LeftBracket => audio.change_volume(-1),
+                    Keycode::RightBracket => audio.change_volume(1),
+                    Keycode::B => {
+                        bot = match bot {
+                            Some(_) => None,
+                            None => Some(Bot::new()),
+                        };
+                        next_bot_move_time = Instant::now();
+                    }
+                    Keycode::H => {
+                        game.hint();
+                        redraw = true;
+                    }
+                    Keycode::P => {
+                        game.cycle_heatmap();
+                        redraw = true;
+                    }
+                    Keycode::F => {
+                        show_fps = !show_fps;
+                        redraw = true;
+                    }
+                    Keycode::S => {
+                        let path = options.save_layout.as_path();
+                        let replaced = path.exists();
+                        let message = match layout::save(game.get_board(), path) {
+                            Ok(()) if replaced => format!("Layout saved over {}", path.display()),
+                            Ok(()) => format!("Layout saved to {}", path.display()),
+                            Err(error) => format!("{}: {}", path.display(), error),
+                        };
+                        game.set_message(message);
+                        redraw = true;
+                    }
+                    _ => {}
+                },
                 Event::Quit { .. } => {
                     break 'running;
                 }
                 _ => {}
             }
         }
+
+        let now = Instant::now();
+        if let Some(active_bot) = bot.as_mut() {
+            if now >= next_bot_move_time {
+                match game.bot_move(active_bot) {
+                    Some(outcome) => {
+                        save_score(&mut game, &options, outcome);
+                        if let Some(sound) = Sound::for_click(outcome) {
+                            audio.play(sound);
+                        }
+                        redraw = true;
+                    }
+                    None => bot = None,
+                }
+                next_bot_move_time = now + bot_move_time;
+            }
+        }
+
+        if redraw || ((show_fps || game.needs_frame()) && now >= next_frame_time) {
+            game.render(&mut canvas, &mut texture_cache)?;
+            if show_fps {
+                frame_counter.render(&mut canvas, &mut texture_cache, game.get_last_render_duration())?;
+            }
+            canvas.present();
+            frame_counter.frame();
+
+            redraw = false;
+            next_frame_time = now + Duration::from_millis(FRAME_TIME_MS);
+        }
+    }
+
+    Ok(())
+}
+
+// Same setup when starting and restarting, only the animations can be toggled in between
+fn new_game(layout: Option<&Board>, options: &Options, animations: bool) -> Game {
+    let mut game = match layout {
+        Some(board) => Game::with_board(GAME_PARAMS.0, GAME_PARAMS.1, GAME_PARAMS.4, board.clone()),
+        None => Game::from_params(GAME_PARAMS),
+    };
+    game.set_animations_enabled(animations);
+    game.set_forgiving(options.forgiving);
+    if options.torus {
+        game.set_topology(Topology::Torus);
+    }
+    game
+}
+
+// Finished games go to the scores file when there is one, a failure only shows a message
+fn save_score(game: &mut Game, options: &Options, outcome: ClickOutcome) {
+    if outcome != ClickOutcome::Won && outcome != ClickOutcome::Lost {
+        return;
+    }
+    let (path, line) = match (options.scores.as_ref(), game.get_score_line()) {
+        (Some(path), Some(line)) => (path, line),
+        _ => return,
+    };
+    if let Err(error) = scores::append(path, &line) {
+        game.set_message(format!("Couldn't save the score to {}: {}", path.display(), error));
     }
 }
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Delay between consecutive rings of a flood reveal
+const REVEAL_STEP: Duration = Duration::from_millis(20);
+const REVEAL_DURATION: Duration = Duration::from_millis(150);
+const FLAG_DROP_DURATION: Duration = Duration::from_millis(120);
+// Delay between consecutive rings of mines going off after a loss
+const EXPLOSION_STEP: Duration = Duration::from_millis(60);
+const EXPLOSION_DURATION: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationKind {
+    Reveal,
+    FlagDrop,
+    Explosion,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CellAnimation {
+    kind: AnimationKind,
+    start: Instant,
+    duration: Duration,
+}
+
+pub struct Animations {
+    enabled: bool,
+    cells: HashMap<usize, CellAnimation>,
+}
+
+impl Animations {
+    pub fn new(enabled: bool) -> Animations {
+        Animations {
+            enabled,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Returns cells that were animated, they have to be redrawn in their final state
+    pub fn set_enabled(&mut self, enabled: bool) -> Vec<usize> {
+        self.enabled = enabled;
+        self.skip()
+    }
+
+    // Step is how many rings away from the action the cell is, further cells start later
+    pub fn add(&mut self, index: usize, kind: AnimationKind, step: u32) {
+        if !self.enabled {
+            return;
+        }
+
+        let (step_delay, duration) = match kind {
+            AnimationKind::Reveal => (REVEAL_STEP, REVEAL_DURATION),
+            AnimationKind::FlagDrop => (Duration::from_secs(0), FLAG_DROP_DURATION),
+            AnimationKind::Explosion => (EXPLOSION_STEP, EXPLOSION_DURATION),
+        };
+
+        self.cells.insert(
+            index,
+            CellAnimation {
+                kind,
+                start: Instant::now() + step_delay * step,
+                duration,
+            },
+        );
+    }
+
+    // None when the cell isn't animated, otherwise progress from 0 (not started) to 1 (done)
+    pub fn progress(&self, index: usize, now: Instant) -> Option<(AnimationKind, f32)> {
+        self.cells.get(&index).map(|animation| {
+            let elapsed = now.saturating_duration_since(animation.start);
+            let progress = elapsed.as_secs_f32() / animation.duration.as_secs_f32();
+            (animation.kind, progress.min(1f32))
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.cells.is_empty()
+    }
+
+    pub fn animated_cells(&self) -> Vec<usize> {
+        self.cells.keys().cloned().collect()
+    }
+
+    // Finished animations are dropped after their last frame was drawn
+    pub fn remove_finished(&mut self, now: Instant) {
+        self.cells
+            .retain(|_, animation| now < animation.start + animation.duration);
+    }
+
+    // Ends all animations at once, returns cells that have to be redrawn
+    pub fn skip(&mut self) -> Vec<usize> {
+        self.cells.drain().map(|(index, _)| index).collect()
+    }
+}
@@ -0,0 +1,42 @@
+extern crate sdl2;
+use crate::mods::error::GameError;
+use sdl2::rwops::RWops;
+use sdl2::ttf::{Font, Sdl2TtfContext};
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub const SOUNDS_DIRECTORY: &str = "assets/sounds";
+const FONT_POINT_SIZE: u16 = 128;
+
+// Compiled in, so the executable doesn't depend on the working directory
+static EMBEDDED_FONT: &[u8] = include_bytes!("../../PxPlus_AmstradPC1512.ttf");
+
+// Looks in the working directory first, then next to the executable
+pub fn find_asset(relative_path: &str) -> Option<PathBuf> {
+    let mut candidates = vec![PathBuf::from(relative_path)];
+    if let Some(directory) = env::current_exe()
+        .ok()
+        .and_then(|executable| executable.parent().map(|p| p.to_path_buf()))
+    {
+        candidates.push(directory.join(relative_path));
+    }
+
+    candidates.into_iter().find(|path| path.exists())
+}
+
+// Built-in font unless a font file is given
+pub fn load_font<'ttf>(
+    context: &'ttf Sdl2TtfContext,
+    path: Option<&Path>,
+) -> Result<Font<'ttf, 'static>, GameError> {
+    if let Some(path) = path {
+        return context
+            .load_font(path, FONT_POINT_SIZE)
+            .map_err(|message| GameError::Font(format!("{}: {}", path.display(), message)));
+    }
+
+    let rwops = RWops::from_bytes(EMBEDDED_FONT).map_err(GameError::Font)?;
+    context
+        .load_font_from_rwops(rwops, FONT_POINT_SIZE)
+        .map_err(GameError::Font)
+}
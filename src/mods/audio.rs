@@ -0,0 +1,145 @@
+extern crate sdl2;
+use minesweeper::engine::board::ClickOutcome;
+use sdl2::mixer::{Channel, Chunk, DEFAULT_CHANNELS, DEFAULT_FORMAT, DEFAULT_FREQUENCY, MAX_VOLUME};
+use sdl2::AudioSubsystem;
+use std::collections::HashMap;
+use std::path::Path;
+
+const VOLUME_STEP: i32 = MAX_VOLUME / 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sound {
+    Reveal,
+    Cascade,
+    Flag,
+    Chord,
+    Win,
+    Loss,
+}
+
+impl Sound {
+    const ALL: [Sound; 6] = [
+        Sound::Reveal,
+        Sound::Cascade,
+        Sound::Flag,
+        Sound::Chord,
+        Sound::Win,
+        Sound::Loss,
+    ];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Sound::Reveal => "reveal.wav",
+            Sound::Cascade => "cascade.wav",
+            Sound::Flag => "flag.wav",
+            Sound::Chord => "chord.wav",
+            Sound::Win => "win.wav",
+            Sound::Loss => "loss.wav",
+        }
+    }
+
+    pub fn for_click(outcome: ClickOutcome) -> Option<Sound> {
+        match outcome {
+            ClickOutcome::Nothing => None,
+            ClickOutcome::Reveal => Some(Sound::Reveal),
+            ClickOutcome::Cascade => Some(Sound::Cascade),
+            ClickOutcome::Flag => Some(Sound::Flag),
+            ClickOutcome::Chord => Some(Sound::Chord),
+            ClickOutcome::Won => Some(Sound::Win),
+            ClickOutcome::Lost => Some(Sound::Loss),
+        }
+    }
+}
+
+// Plays sound effects, without an audio device the game just stays silent
+pub struct Audio {
+    // None when no audio device could be opened, kept alive for as long as the mixer is open
+    audio_subsystem: Option<AudioSubsystem>,
+    chunks: HashMap<Sound, Chunk>,
+    volume: i32,
+    muted: bool,
+}
+
+impl Audio {
+    pub fn new(audio_subsystem: Result<AudioSubsystem, String>, sounds_directory: &Path) -> Audio {
+        let mut audio = Audio {
+            audio_subsystem: None,
+            chunks: HashMap::new(),
+            volume: MAX_VOLUME / 2,
+            muted: false,
+        };
+
+        let opened = audio_subsystem.and_then(|subsystem| {
+            sdl2::mixer::open_audio(DEFAULT_FREQUENCY, DEFAULT_FORMAT, DEFAULT_CHANNELS, 1024)?;
+            Ok(subsystem)
+        });
+        match opened {
+            Ok(subsystem) => audio.audio_subsystem = Some(subsystem),
+            Err(message) => {
+                eprintln!("Sound disabled, no audio device: {}", message);
+                return audio;
+            }
+        }
+
+        for sound in Sound::ALL.iter() {
+            match Chunk::from_file(sounds_directory.join(sound.file_name())) {
+                Ok(chunk) => {
+                    audio.chunks.insert(*sound, chunk);
+                }
+                Err(message) => eprintln!("Couldn't load {}: {}", sound.file_name(), message),
+            }
+        }
+
+        audio.apply_volume();
+        audio
+    }
+
+    pub fn play(&self, sound: Sound) {
+        if self.muted {
+            return;
+        }
+
+        if let Some(chunk) = self.chunks.get(&sound) {
+            let _ = Channel::all().play(chunk, 0);
+        }
+    }
+
+    pub fn change_volume(&mut self, steps: i32) {
+        self.volume = (self.volume + steps * VOLUME_STEP).clamp(0, MAX_VOLUME);
+        self.apply_volume();
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        if muted {
+            self.halt();
+        }
+    }
+
+    fn apply_volume(&self) {
+        if self.audio_subsystem.is_some() {
+            Channel::all().set_volume(self.volume);
+        }
+    }
+
+    fn halt(&self) {
+        if self.audio_subsystem.is_some() {
+            Channel::all().halt();
+        }
+    }
+}
+
+impl Drop for Audio {
+    fn drop(&mut self) {
+        if self.audio_subsystem.is_some() {
+            self.halt();
+            // Chunks have to be freed before the mixer closes
+            self.chunks.clear();
+            sdl2::mixer::close_audio();
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// Smallest square size in pixels, boards that would get smaller squares are panned instead
+pub const MIN_SQUARE_SIZE: u32 = 16;
+pub const MAX_ZOOM: f32 = 8f32;
+pub const ZOOM_STEP: f32 = 1.25f32;
+
+// Mouse has to move this many pixels with a button held before it counts as a drag
+pub const DRAG_THRESHOLD: i32 = 4;
+
+pub struct Camera {
+    // Multiplier of the base square size, 1 means the whole board fits (or squares are minimal)
+    zoom: f32,
+    // Offset from the centred position, in pixels
+    pan: (i32, i32),
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        Camera {
+            zoom: 1f32,
+            pan: (0, 0),
+        }
+    }
+
+    pub fn square_size(&self, window: (u32, u32), board: (u32, u32)) -> u32 {
+        let fit = (window.0 / board.0).min(window.1 / board.1);
+        (fit.max(MIN_SQUARE_SIZE) as f32 * self.zoom) as u32
+    }
+
+    // Screen position of the board's top left corner
+    pub fn offset(&self, window: (u32, u32), board: (u32, u32)) -> (i32, i32) {
+        let square_size = self.square_size(window, board);
+        (
+            (window.0 as i32 - (square_size * board.0) as i32) / 2 + self.pan.0,
+            (window.1 as i32 - (square_size * board.1) as i32) / 2 + self.pan.1,
+        )
+    }
+
+    pub fn pan_by(&mut self, dx: i32, dy: i32, window: (u32, u32), board: (u32, u32)) {
+        self.pan.0 += dx;
+        self.pan.1 += dy;
+        self.clamp(window, board);
+    }
+
+    // Zooms keeping the board point under (x, y) in place
+    pub fn zoom_at(&mut self, steps: i32, x: i32, y: i32, window: (u32, u32), board: (u32, u32)) {
+        let old_size = self.square_size(window, board) as f32;
+        let old_offset = self.offset(window, board);
+
+        self.zoom = (self.zoom * ZOOM_STEP.powi(steps)).clamp(1f32, MAX_ZOOM);
+
+        let new_size = self.square_size(window, board) as f32;
+        let board_x = (x - old_offset.0) as f32 / old_size;
+        let board_y = (y - old_offset.1) as f32 / old_size;
+        let centred = self.offset(window, board);
+
+        self.pan.0 += x - (board_x * new_size) as i32 - centred.0;
+        self.pan.1 += y - (board_y * new_size) as i32 - centred.1;
+        self.clamp(window, board);
+    }
+
+    pub fn reset(&mut self) {
+        self.zoom = 1f32;
+        self.pan = (0, 0);
+    }
+
+    // Board that fits stays centred, bigger one can't leave a gap at the window's edge
+    pub fn clamp(&mut self, window: (u32, u32), board: (u32, u32)) {
+        let square_size = self.square_size(window, board);
+        let board_pixels = (square_size * board.0, square_size * board.1);
+
+        self.pan.0 = Camera::clamp_axis(self.pan.0, window.0, board_pixels.0);
+        self.pan.1 = Camera::clamp_axis(self.pan.1, window.1, board_pixels.1);
+    }
+
+    fn clamp_axis(pan: i32, window: u32, board_pixels: u32) -> i32 {
+        if board_pixels <= window {
+            0
+        } else {
+            let limit = (board_pixels - window) as i32 / 2;
+            pan.clamp(-limit, limit)
+        }
+    }
+
+    pub fn is_board_visible_whole(&self, window: (u32, u32), board: (u32, u32)) -> bool {
+        let square_size = self.square_size(window, board);
+        square_size * board.0 <= window.0 && square_size * board.1 <= window.1
+    }
+}
@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fmt;
+
+// Everything that can stop the game, shown to the player instead of a panic
+#[derive(Debug)]
+pub enum GameError {
+    // Bad command line, exits with a different code than the rest
+    Arguments(String),
+    // SDL itself or one of its subsystems couldn't start
+    Sdl(String),
+    Font(String),
+    Layout(String),
+    Window(String),
+    Canvas(String),
+    Render(String),
+}
+
+impl GameError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GameError::Arguments(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Arguments(message) => write!(f, "{}", message),
+            GameError::Sdl(message) => write!(f, "Couldn't initialize SDL: {}", message),
+            GameError::Font(message) => write!(f, "Couldn't load the font: {}", message),
+            GameError::Layout(message) => write!(f, "{}", message),
+            GameError::Window(message) => write!(f, "Couldn't open the window: {}", message),
+            GameError::Canvas(message) => write!(f, "Couldn't create the renderer: {}", message),
+            GameError::Render(message) => write!(f, "Rendering failed: {}", message),
+        }
+    }
+}
+
+impl Error for GameError {}
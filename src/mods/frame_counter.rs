@@ -0,0 +1,73 @@
+extern crate sdl2;
+use crate::mods::error::GameError;
+use crate::mods::game_canvas::GameCanvas;
+use crate::mods::texture_cache::TextureCache;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use std::time::{Duration, Instant};
+
+const OVERLAY_TEXT_HEIGHT: u32 = 16;
+const OVERLAY_MARGIN: u32 = 8;
+
+// Counts presented frames, the count of the last full second is shown as FPS
+pub struct FrameCounter {
+    frames: u32,
+    second_start: Instant,
+    fps: u32,
+}
+
+impl FrameCounter {
+    pub fn new() -> FrameCounter {
+        FrameCounter {
+            frames: 0,
+            second_start: Instant::now(),
+            fps: 0,
+        }
+    }
+
+    pub fn frame(&mut self) {
+        self.frames += 1;
+        if self.second_start.elapsed() >= Duration::from_secs(1) {
+            self.fps = self.frames;
+            self.frames = 0;
+            self.second_start = Instant::now();
+        }
+    }
+
+    pub fn render(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        render_duration: &Duration,
+    ) -> Result<(), GameError> {
+        let textures = if texture_cache.is_enabled() {
+            format!("{} textures", texture_cache.len())
+        } else {
+            "texture cache off".to_string()
+        };
+        let text = format!(
+            "{} FPS {:.2} ms {}",
+            self.fps,
+            render_duration.as_secs_f64() * 1000f64,
+            textures
+        );
+        let width = texture_cache.text_width(&text, OVERLAY_TEXT_HEIGHT)?;
+        let (window_width, window_height) = canvas.window().size();
+        let x = window_width as i32 - (width + OVERLAY_MARGIN) as i32;
+        let y = window_height as i32 - (OVERLAY_TEXT_HEIGHT + OVERLAY_MARGIN) as i32;
+
+        canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+        let _ = canvas.fill_rect(Rect::new(x, y, width, OVERLAY_TEXT_HEIGHT));
+        canvas.draw_text_line(
+            x,
+            y,
+            OVERLAY_TEXT_HEIGHT,
+            texture_cache,
+            Color::from((255u8, 255u8, 0u8)),
+            &text,
+        )?;
+        Ok(())
+    }
+}
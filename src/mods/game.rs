@@ -1,33 +1,50 @@
-extern crate rand;
 extern crate sdl2;
 
-use crate::GameCanvas;
-use crate::*;
+use crate::mods::animation::{AnimationKind, Animations};
+use crate::mods::camera::Camera;
+use crate::mods::error::GameError;
+use crate::mods::game_canvas::GameCanvas;
+use crate::mods::heatmap::Heatmap;
+use crate::mods::texture_cache::TextureCache;
+use minesweeper::engine::board::{Action, Board, ClickOutcome, GameStatus, Topology};
+use minesweeper::engine::bot::Bot;
+use minesweeper::engine::field::*;
+use minesweeper::engine::hint::{self, Hint};
+use minesweeper::engine::metrics::{self, Metrics};
+use minesweeper::engine::scores;
+use minesweeper::engine::solver;
+use minesweeper::engine::stats::{self, ClickTally, Results};
 
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::Canvas;
+use sdl2::render::{BlendMode, Canvas};
 use sdl2::video::Window;
-
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub enum GameStatus {
-    Lost,
-    Won,
-    Playing,
-}
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+// Longer side of the minimap in pixels and its distance from the window's edges
+const MINIMAP_MAX_SIZE: u32 = 160;
+const MINIMAP_MARGIN: u32 = 8;
+const HUD_TEXT_HEIGHT: u32 = 24;
+const HUD_MARGIN: u32 = 8;
+const HINT_TEXT_HEIGHT: u32 = 16;
+const RESULTS_TEXT_HEIGHT: u32 = 20;
+// Cells shown across the edges of a torus board are darkened by this much
+const WRAPPED_SHADE_ALPHA: u8 = 160;
 
 pub struct Game {
     window_width: u32,
     window_height: u32,
 
-    game_width: u32,
-    game_height: u32,
+    board: Board,
 
     game_square_dimensions: (u32, u32),
     game_square_border_percentage: (f32, f32),
-
-    game_fields_array: Vec<Field>,
-    game_mines_count: u32,
+    // Offset of the board's top left corner, centres the board in the window
+    game_board_offset: (i32, i32),
+    camera: Camera,
+    animations: Animations,
+    heatmap: Heatmap,
 
     // First one for border, second for filling, 3rd is special for pointer's text
     game_mine_color: [Color; 2],
@@ -36,7 +53,31 @@ pub struct Game {
     game_marked_color: [Color; 2],
     game_pointer_color: [Color; 3],
 
-    game_status: GameStatus,
+    // Cells changed since the last render, everything is redrawn when game_full_redraw is set
+    game_dirty_cells: Vec<usize>,
+    game_full_redraw: bool,
+
+    // Highlighted until the next click
+    game_hint: Option<Hint>,
+    // Any hint or move made by the bot makes the game assisted
+    game_hints_used: u32,
+    game_bot_moves: u32,
+    // No safe move is left, updated after every click that changed the board. Only
+    // forgiving mode shows it, a solve after every click isn't worth it otherwise
+    game_guess_forced: bool,
+
+    // Difficulty of the layout, taken when the game ends as forgiving mode can move mines
+    game_metrics: Option<Metrics>,
+    // Taken when the game ends, the timer keeps its value after that
+    game_results: Option<Results>,
+
+    // Shown under the HUD until the next click, e.g. where the layout was saved
+    game_message: Option<String>,
+
+    // Timer's value shown by the last render
+    game_rendered_second: u64,
+
+    last_render_duration: Duration,
 }
 
 impl Game {
@@ -47,17 +88,32 @@ impl Game {
         game_height: u32,
         game_square_border_percentage: (f32, f32),
         game_mines_count: u32,
+    ) -> Game {
+        Game::with_board(
+            window_width,
+            window_height,
+            game_square_border_percentage,
+            Board::new(game_width, game_height, game_mines_count),
+        )
+    }
+
+    // Game on a prepared board, e.g. a layout loaded from a file
+    pub fn with_board(
+        window_width: u32,
+        window_height: u32,
+        game_square_border_percentage: (f32, f32),
+        board: Board,
     ) -> Game {
         let mut game = Game {
-            game_status: GameStatus::Playing,
             window_width,
             window_height,
-            game_width,
-            game_height,
-            game_square_dimensions: (window_width / game_width, window_height / game_height),
+            board,
+            game_square_dimensions: (0, 0),
             game_square_border_percentage,
-            game_fields_array: Vec::with_capacity(game_width as usize * game_height as usize),
-            game_mines_count,
+            game_board_offset: (0, 0),
+            camera: Camera::new(),
+            animations: Animations::new(true),
+            heatmap: Heatmap::new(),
             game_mine_color: [Color::from((128, 0, 0)), Color::from((184, 0, 0))],
             game_unrevealed_color: [Color::from((32, 32, 32)), Color::from((64, 64, 64))],
             game_marked_color: [Color::from((102, 0, 51)), Color::from((255, 0, 102))],
@@ -67,10 +123,20 @@ impl Game {
                 Color::from((51, 204, 255)),
             ],
             game_revealed_color: [Color::from((0, 0, 0)), Color::from((0, 0, 0))],
+            game_dirty_cells: Vec::new(),
+            game_full_redraw: true,
+            game_hint: None,
+            game_hints_used: 0,
+            game_bot_moves: 0,
+            game_guess_forced: false,
+            game_metrics: None,
+            game_results: None,
+            game_message: None,
+            game_rendered_second: 0,
+            last_render_duration: Duration::from_secs(0),
         };
 
-        game.setup_mines();
-        game.setup_pointers();
+        game.setup_layout();
         game
     }
 
@@ -84,182 +150,263 @@ impl Game {
     pub fn get_window_height(&self) -> &u32 {
         &self.window_height
     }
-    pub fn get_game_square_dimensions(&self) -> &(u32, u32) {
-        &self.game_square_dimensions
+
+    pub fn get_cell_rect(&self, x: u32, y: u32) -> Rect {
+        Rect::new(
+            x as i32 * self.game_square_dimensions.0 as i32 + self.game_board_offset.0,
+            y as i32 * self.game_square_dimensions.1 as i32 + self.game_board_offset.1,
+            self.game_square_dimensions.0,
+            self.game_square_dimensions.1,
+        )
+    }
+
+    // Part of the square inside its border
+    pub fn get_inner_rect(&self, outer_rect: Rect) -> Rect {
+        let border_percentage = self.game_square_border_percentage;
+        Rect::new(
+            (border_percentage.0 * outer_rect.width() as f32) as i32 + outer_rect.x(),
+            (border_percentage.1 * outer_rect.height() as f32) as i32 + outer_rect.y(),
+            outer_rect.width() - (border_percentage.0 * outer_rect.width() as f32 * 2f32) as u32,
+            outer_rect.height() - (border_percentage.1 * outer_rect.height() as f32 * 2f32) as u32,
+        )
+    }
+
+    // Whether something changed on its own since the last render, like the timer
+    pub fn needs_frame(&self) -> bool {
+        self.animations.is_active() || self.board.get_elapsed_time().as_secs() != self.game_rendered_second
+    }
+
+    pub fn is_animations_enabled(&self) -> bool {
+        self.animations.is_enabled()
     }
 
-    pub fn get_game_square_border_percentage(&self) -> &(f32, f32) {
-        &self.game_square_border_percentage
+    pub fn set_animations_enabled(&mut self, enabled: bool) {
+        let animated_cells = self.animations.set_enabled(enabled);
+        self.game_dirty_cells.extend(animated_cells);
     }
 
-    fn arr2d_arr1d(&self, x: i32, y: i32) -> usize {
-        (x + y * self.game_width as i32) as usize
+    // Changes the counts, so only before the first click
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.board.set_topology(topology);
+        self.game_full_redraw = true;
     }
 
-    fn setup_mines(&mut self) {
-        let mut empty_fields: Vec<usize> = Vec::with_capacity(self.game_fields_array.len());
-        for i in 0..self.game_width as usize * self.game_height as usize {
-            self.game_fields_array.push(Field {
-                field_status: FieldStatus::Unrevealed,
-                field_type: FieldType::Empty,
-                is_marked: false,
-            });
-            empty_fields.push(i);
+    pub fn set_forgiving(&mut self, forgiving: bool) {
+        self.board.set_forgiving(forgiving);
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.game_message = Some(message);
+    }
+
+    pub fn skip_animations(&mut self) {
+        let animated_cells = self.animations.skip();
+        self.game_dirty_cells.extend(animated_cells);
+    }
+
+    // Mine probabilities over hidden cells: off, tinted, tinted with percentages
+    pub fn cycle_heatmap(&mut self) {
+        self.heatmap.cycle();
+    }
+
+    // Next safe cell, or the least risky one, highlighted with the reasoning behind it
+    pub fn hint(&mut self) {
+        if let Some(hint) = hint::hint(&self.board) {
+            self.game_hint = Some(hint);
+            self.game_hints_used += 1;
         }
+    }
 
-        for _ in 0..self.game_mines_count {
-            let index = ((rand::random::<u64>() / 160u64 + empty_fields.len() as u64 * 37u64)
-                % empty_fields.len() as u64) as usize;
-            self.game_fields_array[empty_fields[index]].field_type = FieldType::Mine;
-            empty_fields.remove(index);
+    pub fn get_click_tally(&self) -> ClickTally {
+        stats::tally(&self.board)
+    }
+
+    pub fn is_assisted(&self) -> bool {
+        self.game_hints_used > 0 || self.game_bot_moves > 0
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    // Line for the scores file, once the game has ended
+    pub fn get_score_line(&self) -> Option<String> {
+        match (self.game_results.as_ref(), self.game_metrics.as_ref()) {
+            (Some(results), Some(metrics)) => Some(scores::line(&self.board, results, metrics)),
+            _ => None,
         }
     }
 
-    fn setup_pointers(&mut self) {
-        for x in 0..self.game_width as i32 {
-            for y in 0..self.game_height as i32 {
-                let middle_cell_index_index = self.get_cell_index(x, y);
+    // Time spent drawing the last frame, before presenting it
+    pub fn get_last_render_duration(&self) -> &Duration {
+        &self.last_render_duration
+    }
 
-                if middle_cell_index_index.is_none() {
-                    continue;
-                }
+    pub fn resize(&mut self, window_width: u32, window_height: u32) {
+        self.window_width = window_width;
+        self.window_height = window_height;
+        self.setup_layout();
+    }
 
-                if self.game_fields_array[middle_cell_index_index.unwrap()].field_type
-                    == FieldType::Mine
-                {
-                    let surrounding_cell_indexs = [
-                        (x - 1, y - 1),
-                        (x, y - 1),
-                        (x + 1, y - 1),
-                        (x - 1, y),
-                        (x + 1, y),
-                        (x - 1, y + 1),
-                        (x, y + 1),
-                        (x + 1, y + 1),
-                    ];
-
-                    for field in surrounding_cell_indexs.iter() {
-                        let index = self.get_cell_index(field.0, field.1);
-
-                        if let Some(i) = index {
-                            match self.game_fields_array[i].field_type {
-                                FieldType::Empty => {
-                                    self.game_fields_array[i].field_type =
-                                        FieldType::Pointer { mines_nearby: 1 };
-                                }
-                                FieldType::Pointer { mines_nearby } => {
-                                    self.game_fields_array[i].field_type = FieldType::Pointer {
-                                        mines_nearby: mines_nearby + 1,
-                                    };
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-            }
+    pub fn zoom(&mut self, steps: i32, x: i32, y: i32) {
+        let (window, board) = self.layout_dimensions();
+        self.camera.zoom_at(steps, x, y, window, board);
+        self.setup_layout();
+    }
+
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        let (window, board) = self.layout_dimensions();
+        self.camera.pan_by(dx, dy, window, board);
+        self.setup_layout();
+    }
+
+    pub fn reset_camera(&mut self) {
+        self.camera.reset();
+        self.setup_layout();
+    }
+
+    fn layout_dimensions(&self) -> ((u32, u32), (u32, u32)) {
+        (
+            (self.window_width, self.window_height),
+            (*self.board.get_width(), *self.board.get_height()),
+        )
+    }
+
+    // Square size and board position as seen through the camera
+    fn setup_layout(&mut self) {
+        let (window, board) = self.layout_dimensions();
+        self.camera.clamp(window, board);
+
+        let square_size = self.camera.square_size(window, board);
+        self.game_square_dimensions = (square_size, square_size);
+        self.game_board_offset = self.camera.offset(window, board);
+        self.game_full_redraw = true;
+    }
+
+    // Used when the window's content got lost, e.g. after it was exposed
+    pub fn request_full_redraw(&mut self) {
+        self.game_full_redraw = true;
+    }
+
+    // Range of columns and rows that are at least partially inside the window
+    fn visible_cells(&self) -> ((u32, u32), (u32, u32)) {
+        let first = |offset: i32, size: u32| (-offset).max(0) as u32 / size;
+        let last = |offset: i32, size: u32, window: u32, count: u32| {
+            ((window as i32 - offset).max(0) as u32).div_ceil(size).min(count)
+        };
+
+        (
+            (
+                first(self.game_board_offset.0, self.game_square_dimensions.0),
+                last(
+                    self.game_board_offset.0,
+                    self.game_square_dimensions.0,
+                    self.window_width,
+                    *self.board.get_width(),
+                ),
+            ),
+            (
+                first(self.game_board_offset.1, self.game_square_dimensions.1),
+                last(
+                    self.game_board_offset.1,
+                    self.game_square_dimensions.1,
+                    self.window_height,
+                    *self.board.get_height(),
+                ),
+            ),
+        )
+    }
+
+    fn screen_to_cell(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let x = x - self.game_board_offset.0;
+        let y = y - self.game_board_offset.1;
+
+        if x < 0 || y < 0 {
+            return None;
         }
+
+        let cell = (
+            x / self.game_square_dimensions.0 as i32,
+            y / self.game_square_dimensions.1 as i32,
+        );
+        self.board.get_cell_index(cell.0, cell.1).map(|_| cell)
     }
 
     pub fn render(
         &mut self,
         canvas: &mut Canvas<Window>,
-        font: &sdl2::ttf::Font,
-        texture_creator: &sdl2::render::TextureCreator<sdl2::video::WindowContext>,
-    ) {
-        canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
-        canvas.clear();
-
-        for x in 0..self.game_width {
-            for y in 0..self.game_height {
-                let element = self.game_fields_array[self.arr2d_arr1d(x as i32, y as i32)];
-
-                match element.field_status {
-                    FieldStatus::Unrevealed => {
-                        if element.is_marked {
-                            canvas.draw_square(
-                                x,
-                                y,
-                                &self,
-                                self.game_marked_color[0],
-                                self.game_marked_color[1],
-                            );
+        texture_cache: &mut TextureCache,
+    ) -> Result<(), GameError> {
+        let render_start = Instant::now();
+
+        // Animated cells change every frame
+        let animated_cells = self.animations.animated_cells();
+        self.game_dirty_cells.extend(animated_cells);
+
+        let full_redraw = self.game_full_redraw;
+        match texture_cache.take_board_texture(self.window_width, self.window_height) {
+            Some((mut board_texture, is_new)) => {
+                let mut result = Ok(());
+                canvas
+                    .with_texture_canvas(&mut board_texture, |board_canvas| {
+                        result = if full_redraw || is_new {
+                            self.render_cells(board_canvas, texture_cache, render_start)
                         } else {
-                            canvas.draw_square(
-                                x,
-                                y,
-                                &self,
-                                self.game_unrevealed_color[0],
-                                self.game_unrevealed_color[1],
-                            )
-                        }
-                    }
-                    FieldStatus::Revealed => match element.field_type {
-                        FieldType::Empty => {
-                            canvas.draw_square(
-                                x,
-                                y,
-                                &self,
-                                self.game_revealed_color[0],
-                                self.game_revealed_color[1],
-                            );
-                        }
-                        FieldType::Mine => {
-                            canvas.draw_square(
-                                x,
-                                y,
-                                &self,
-                                self.game_mine_color[0],
-                                self.game_mine_color[1],
-                            );
-                        }
-                        FieldType::Pointer { mines_nearby } => {
-                            canvas.draw_square(
-                                x,
-                                y,
-                                &self,
-                                self.game_pointer_color[0],
-                                self.game_pointer_color[1],
-                            );
-                            let inner_rect = Rect::new(
-                                (self.game_square_border_percentage.0
-                                    * self.game_square_dimensions.0 as f32)
-                                    as i32
-                                    + x as i32 * self.game_square_dimensions.0 as i32,
-                                (self.game_square_border_percentage.1
-                                    * self.game_square_dimensions.1 as f32)
-                                    as i32
-                                    + y as i32 * self.game_square_dimensions.1 as i32,
-                                self.game_square_dimensions.0
-                                    - (self.game_square_border_percentage.0
-                                        * self.game_square_dimensions.0 as f32
-                                        * 2f32) as u32,
-                                self.game_square_dimensions.1
-                                    - (self.game_square_border_percentage.1
-                                        * self.game_square_dimensions.1 as f32
-                                        * 2f32) as u32,
-                            );
-                            canvas.draw_text(
-                                inner_rect,
-                                font,
-                                texture_creator,
-                                self.game_pointer_color[2],
-                                mines_nearby.to_string().as_str(),
-                            )
-                        }
-                    },
+                            self.render_dirty_cells(board_canvas, texture_cache, render_start)
+                        };
+                    })
+                    .map_err(|e| GameError::Render(e.to_string()))?;
+                result?;
+
+                canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+                canvas.clear();
+                canvas
+                    .copy(&board_texture, None, None)
+                    .map_err(GameError::Render)?;
+                texture_cache.return_board_texture(board_texture);
+            }
+            // No render target support, everything has to be drawn straight to the window
+            None => self.render_cells(canvas, texture_cache, render_start)?,
+        }
+        self.game_dirty_cells.clear();
+        self.game_full_redraw = false;
+        self.animations.remove_finished(render_start);
+
+        if *self.board.get_topology() == Topology::Torus {
+            self.render_wrapped_edges(canvas, texture_cache)?;
+        }
+
+        if self.heatmap.is_enabled() && *self.board.get_status() == GameStatus::Playing {
+            let (columns, rows) = self.visible_cells();
+            let mut cells = Vec::new();
+            for x in columns.0..columns.1 {
+                for y in rows.0..rows.1 {
+                    let index = self.board.arr2d_arr1d(x as i32, y as i32);
+                    cells.push((index, self.get_cell_rect(x, y)));
                 }
             }
+            self.heatmap.render(canvas, texture_cache, &self.board, &cells)?;
+        }
+
+        if let Some(hint) = self.game_hint.as_ref() {
+            self.render_hint(canvas, texture_cache, hint)?;
         }
-        match self.game_status {
+
+        let (window, board) = self.layout_dimensions();
+        if !self.camera.is_board_visible_whole(window, board) {
+            self.render_minimap(canvas);
+        }
+
+        // Result is shown once the board stopped moving
+        match self.board.get_status() {
+            _ if self.animations.is_active() => {}
             GameStatus::Lost => {
                 canvas.draw_text(
                     Rect::from((0, 0, self.window_width, self.window_height / 2)),
-                    font,
-                    texture_creator,
+                    texture_cache,
                     Color::from((255u8, 255u8, 255u8)),
                     "Defeat",
-                );
+                )?;
                 canvas.draw_text(
                     Rect::from((
                         0,
@@ -267,174 +414,571 @@ impl Game {
                         self.window_width,
                         self.window_height / 4,
                     )),
-                    font,
-                    texture_creator,
+                    texture_cache,
                     Color::from((255u8, 255u8, 255u8)),
                     "Press R to restart the game.",
-                );
+                )?;
             }
 
             GameStatus::Won => {
                 canvas.draw_text(
                     Rect::from((0, 0, self.window_width, self.window_height)),
-                    font,
-                    texture_creator,
+                    texture_cache,
                     Color::from((255u8, 255u8, 255u8)),
                     "Victory",
-                );
+                )?;
+                if self.is_assisted() {
+                    canvas.draw_text(
+                        Rect::from((
+                            0,
+                            self.window_height as i32 * 3 / 4,
+                            self.window_width,
+                            self.window_height / 8,
+                        )),
+                        texture_cache,
+                        Color::from((255u8, 255u8, 255u8)),
+                        &format!(
+                            "Assisted, {} hints and {} bot moves",
+                            self.game_hints_used, self.game_bot_moves
+                        ),
+                    )?;
+                }
             }
             _ => {}
         }
+        if let Some(results) = self.game_results.as_ref() {
+            if !self.animations.is_active() {
+                self.render_results(canvas, texture_cache, results)?;
+            }
+        }
 
-        canvas.present();
+        self.render_hud(canvas, texture_cache)?;
+
+        self.game_rendered_second = self.board.get_elapsed_time().as_secs();
+        self.last_render_duration = render_start.elapsed();
+        Ok(())
     }
 
-    fn get_cell_index(&self, x: i32, y: i32) -> Option<usize> {
-        if x < 0 || y < 0 || x as u32 > self.game_width - 1 || y as u32 > self.game_height - 1 {
-            None
-        } else {
-            Some(self.arr2d_arr1d(x, y))
+    fn render_hud(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+    ) -> Result<(), GameError> {
+        let mut text = format!("{:03}", self.board.get_elapsed_time().as_secs());
+        if self.is_assisted() {
+            text += &format!(" ?{}", self.game_hints_used);
+        }
+        if self.game_guess_forced {
+            text += "  Guess, it's safe";
+        }
+        let tally = self.get_click_tally();
+        if tally.total() > 0 {
+            // Useful reveals, chords, flags, unflags, then failed chords and wasted clicks
+            text += &format!(
+                "  R{} C{} F{} U{} X{} W{}",
+                tally.useful_reveals,
+                tally.chords,
+                tally.flags,
+                tally.unflags,
+                tally.failed_chords,
+                tally.wasted
+            );
+        }
+        let width = texture_cache.text_width(&text, HUD_TEXT_HEIGHT)?;
+
+        canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+        let _ = canvas.fill_rect(Rect::new(
+            HUD_MARGIN as i32,
+            HUD_MARGIN as i32,
+            width,
+            HUD_TEXT_HEIGHT,
+        ));
+        canvas.draw_text_line(
+            HUD_MARGIN as i32,
+            HUD_MARGIN as i32,
+            HUD_TEXT_HEIGHT,
+            texture_cache,
+            Color::from((255u8, 255u8, 255u8)),
+            &text,
+        )?;
+
+        if let Some(message) = self.game_message.as_ref() {
+            let y = (HUD_MARGIN * 2 + HUD_TEXT_HEIGHT) as i32;
+            let width = texture_cache.text_width(message, HINT_TEXT_HEIGHT)?;
+            canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+            let _ = canvas.fill_rect(Rect::new(HUD_MARGIN as i32, y, width, HINT_TEXT_HEIGHT));
+            canvas.draw_text_line(
+                HUD_MARGIN as i32,
+                y,
+                HINT_TEXT_HEIGHT,
+                texture_cache,
+                Color::from((255u8, 255u8, 255u8)),
+                message,
+            )?;
         }
+        Ok(())
     }
 
-    fn flood_reveal(&mut self, x: i32, y: i32) {
-        let surrounding_cell_indexes = [
-            (x - 1, y - 1),
-            (x, y - 1),
-            (x + 1, y - 1),
-            (x - 1, y),
-            (x + 1, y),
-            (x - 1, y + 1),
-            (x, y + 1),
-            (x + 1, y + 1),
+    // Ring of cells around a torus board showing the cells across the opposite edges
+    fn render_wrapped_edges(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+    ) -> Result<(), GameError> {
+        let (width, height) = (*self.board.get_width() as i32, *self.board.get_height() as i32);
+        let window = Rect::new(0, 0, self.window_width, self.window_height);
+        let mut shaded = Vec::new();
+        // Rows above and below the board with the corners, then the columns beside it
+        let ring = (-1..=width)
+            .flat_map(|x| [(x, -1), (x, height)])
+            .chain((0..height).flat_map(|y| [(-1, y), (width, y)]));
+        for (x, y) in ring {
+            let rect = Rect::new(
+                x * self.game_square_dimensions.0 as i32 + self.game_board_offset.0,
+                y * self.game_square_dimensions.1 as i32 + self.game_board_offset.1,
+                self.game_square_dimensions.0,
+                self.game_square_dimensions.1,
+            );
+            if !rect.has_intersection(window) {
+                continue;
+            }
+
+            let index = self
+                .board
+                .arr2d_arr1d(x.rem_euclid(width), y.rem_euclid(height));
+            let element = self.board.get_fields_array()[index];
+            let colors = self.get_field_colors(&element);
+            canvas.draw_square_rect(rect, self, colors[0], colors[1]);
+            self.render_pointer_text(canvas, texture_cache, &element, rect)?;
+            shaded.push(rect);
+        }
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, WRAPPED_SHADE_ALPHA));
+        let _ = canvas.fill_rects(&shaded);
+        canvas.set_blend_mode(BlendMode::None);
+        Ok(())
+    }
+
+    // Lines of statistics along the bottom of the window once the game is over
+    fn render_results(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        results: &Results,
+    ) -> Result<(), GameError> {
+        let mut lines = vec![
+            format!(
+                "Time {:.2}s  3BV {}/{}  3BV/s {:.2}",
+                results.time.as_secs_f64(),
+                results.solved_three_bv,
+                results.three_bv,
+                results.three_bv_per_second()
+            ),
+            format!(
+                "Clicks {} ({} left, {} right, {} chord, {} wasted)  Efficiency {:.0}%",
+                results.clicks(),
+                results.left_clicks,
+                results.right_clicks,
+                results.chords,
+                results.wasted_clicks,
+                results.efficiency() * 100f64
+            ),
         ];
+        if let Some(estimated_time) = results.estimated_time() {
+            lines.push(format!(
+                "Estimated time {:.1}s",
+                estimated_time.as_secs_f64()
+            ));
+        }
+        if let Some(metrics) = self.game_metrics.as_ref() {
+            lines.push(format!(
+                "Openings {}  Islands {}  ZiNi {}",
+                metrics.openings, metrics.islands, metrics.zini
+            ));
+        }
 
-        let cell_index = self.get_cell_index(x, y);
-
-        if let Some(c) = cell_index {
-            if self.game_fields_array[c].field_status == FieldStatus::Revealed {
-                return;
-            } else {
-                self.game_fields_array[c].field_status = FieldStatus::Revealed;
-                match self.game_fields_array[c].field_type {
-                    FieldType::Pointer { mines_nearby } => {
-                        let mut marked_around = 0;
-                        for cell_index in surrounding_cell_indexes.iter() {
-                            if let Some(index) = self.get_cell_index(cell_index.0, cell_index.1) {
-                                if self.game_fields_array[index].is_marked {
-                                    marked_around += 1;
-                                }
-                            }
-                        }
-
-                        if marked_around != 0 {
-                            if marked_around == mines_nearby {
-                                for cell_index in surrounding_cell_indexes.iter() {
-                                    if let Some(index) =
-                                        self.get_cell_index(cell_index.0, cell_index.1)
-                                    {
-                                        if !self.game_fields_array[index].is_marked {
-                                            self.flood_reveal(cell_index.0, cell_index.1);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        return;
-                    }
-                    _ => {}
-                }
-            }
+        let mut y = self.window_height as i32
+            - (lines.len() as u32 * RESULTS_TEXT_HEIGHT + HUD_MARGIN) as i32;
+        for line in lines.iter() {
+            let width = texture_cache.text_width(line, RESULTS_TEXT_HEIGHT)?;
+            canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+            let _ = canvas.fill_rect(Rect::new(HUD_MARGIN as i32, y, width, RESULTS_TEXT_HEIGHT));
+            canvas.draw_text_line(
+                HUD_MARGIN as i32,
+                y,
+                RESULTS_TEXT_HEIGHT,
+                texture_cache,
+                Color::from((255u8, 255u8, 255u8)),
+                line,
+            )?;
+            y += RESULTS_TEXT_HEIGHT as i32;
+        }
+        Ok(())
+    }
+
+    // Hinted cell outlined, the explanation along the bottom of the window
+    fn render_hint(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        hint: &Hint,
+    ) -> Result<(), GameError> {
+        let color = if hint.is_certain {
+            Color::from((0u8, 255u8, 0u8))
         } else {
-            return;
+            Color::from((255u8, 200u8, 0u8))
+        };
+
+        let mut rect = self.get_cell_rect(hint.cell.0 as u32, hint.cell.1 as u32);
+        canvas.set_draw_color(color);
+        for _ in 0..3 {
+            let _ = canvas.draw_rect(rect);
+            rect = Rect::new(
+                rect.x() + 1,
+                rect.y() + 1,
+                rect.width().saturating_sub(2),
+                rect.height().saturating_sub(2),
+            );
         }
 
-        for field in surrounding_cell_indexes.iter() {
-            self.flood_reveal(field.0, field.1);
+        let y = self.window_height as i32 - (HINT_TEXT_HEIGHT + HUD_MARGIN) as i32;
+        let width = texture_cache.text_width(&hint.explanation, HINT_TEXT_HEIGHT)?;
+        canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+        let _ = canvas.fill_rect(Rect::new(HUD_MARGIN as i32, y, width, HINT_TEXT_HEIGHT));
+        canvas.draw_text_line(
+            HUD_MARGIN as i32,
+            y,
+            HINT_TEXT_HEIGHT,
+            texture_cache,
+            color,
+            &hint.explanation,
+        )?;
+        Ok(())
+    }
+
+    fn render_cells(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        now: Instant,
+    ) -> Result<(), GameError> {
+        canvas.set_draw_color(Color::from((0u8, 0u8, 0u8)));
+        canvas.clear();
+
+        let (columns, rows) = self.visible_cells();
+        for x in columns.0..columns.1 {
+            for y in rows.0..rows.1 {
+                self.render_cell(canvas, texture_cache, x, y, now)?;
+            }
         }
+        Ok(())
     }
 
-    fn mark_field(&mut self, x: i32, y: i32) {
-        let index = self.get_cell_index(x, y);
-        if let Some(i) = index {
-            if self.game_fields_array[i].field_status == FieldStatus::Revealed {
-                return;
+    // Only the cells changed since the last render, the rest is still on the board texture
+    fn render_dirty_cells(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        now: Instant,
+    ) -> Result<(), GameError> {
+        let (columns, rows) = self.visible_cells();
+        for index in self.game_dirty_cells.iter() {
+            let (x, y) = self.board.arr1d_arr2d(*index);
+            let (x, y) = (x as u32, y as u32);
+
+            if x >= columns.0 && x < columns.1 && y >= rows.0 && y < rows.1 {
+                self.render_cell(canvas, texture_cache, x, y, now)?;
             }
+        }
+        Ok(())
+    }
 
-            self.game_fields_array[i].is_marked = !self.game_fields_array[i].is_marked;
+    fn render_cell(
+        &self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        x: u32,
+        y: u32,
+        now: Instant,
+    ) -> Result<(), GameError> {
+        let index = self.board.arr2d_arr1d(x as i32, y as i32);
+        let element = self.board.get_fields_array()[index];
+        let cell_rect = self.get_cell_rect(x, y);
+        let colors = self.get_field_colors(&element);
+
+        match self.animations.progress(index, now) {
+            None => {
+                canvas.draw_square(x, y, self, colors[0], colors[1]);
+                self.render_pointer_text(canvas, texture_cache, &element, cell_rect)?;
+            }
+            // Revealed square grows from the middle of the unrevealed one
+            Some((AnimationKind::Reveal, progress)) => {
+                canvas.draw_square_rect(
+                    cell_rect,
+                    self,
+                    self.game_unrevealed_color[0],
+                    self.game_unrevealed_color[1],
+                );
+                if progress > 0f32 {
+                    let width = (cell_rect.width() as f32 * progress) as u32;
+                    let height = (cell_rect.height() as f32 * progress) as u32;
+                    let mut rect = Rect::new(0, 0, width.max(1), height.max(1));
+                    rect.center_on(cell_rect.center());
+                    canvas.draw_square_rect(rect, self, colors[0], colors[1]);
+                }
+                if progress >= 1f32 {
+                    self.render_pointer_text(canvas, texture_cache, &element, cell_rect)?;
+                }
+            }
+            // Flag slides down into its square from above
+            Some((AnimationKind::FlagDrop, progress)) => {
+                canvas.draw_square_rect(
+                    cell_rect,
+                    self,
+                    self.game_unrevealed_color[0],
+                    self.game_unrevealed_color[1],
+                );
+                let mut rect = cell_rect;
+                rect.offset(0, -((1f32 - progress) * cell_rect.height() as f32) as i32);
+                canvas.set_clip_rect(cell_rect);
+                canvas.draw_square_rect(rect, self, colors[0], colors[1]);
+                canvas.set_clip_rect(None);
+            }
+            // Mine flashes white and fades into its usual color
+            Some((AnimationKind::Explosion, progress)) => {
+                if progress > 0f32 {
+                    let flash = |color: Color| {
+                        let mix = |from: u8| (255f32 + (from as f32 - 255f32) * progress) as u8;
+                        Color::RGB(mix(color.r), mix(color.g), mix(color.b))
+                    };
+                    canvas.draw_square_rect(cell_rect, self, flash(colors[0]), flash(colors[1]));
+                } else {
+                    canvas.draw_square_rect(
+                        cell_rect,
+                        self,
+                        self.game_unrevealed_color[0],
+                        self.game_unrevealed_color[1],
+                    );
+                }
+            }
         }
+        Ok(())
     }
 
-    pub fn click(
-        &mut self,
-        x: i32,
-        y: i32,
-        button: sdl2::mouse::MouseButton,
+    fn get_field_colors(&self, element: &Field) -> [Color; 2] {
+        match element.field_status {
+            FieldStatus::Unrevealed if element.is_marked => self.game_marked_color,
+            FieldStatus::Unrevealed => self.game_unrevealed_color,
+            FieldStatus::Revealed => match element.field_type {
+                FieldType::Empty => self.game_revealed_color,
+                FieldType::Mine => self.game_mine_color,
+                FieldType::Pointer { .. } => {
+                    [self.game_pointer_color[0], self.game_pointer_color[1]]
+                }
+            },
+        }
+    }
+
+    fn render_pointer_text(
+        &self,
         canvas: &mut Canvas<Window>,
-        font: &sdl2::ttf::Font,
-        texture_creator: &sdl2::render::TextureCreator<sdl2::video::WindowContext>,
-    ) {
-        if self.game_status != GameStatus::Playing {
-            return;
+        texture_cache: &mut TextureCache,
+        element: &Field,
+        cell_rect: Rect,
+    ) -> Result<(), GameError> {
+        if let (FieldStatus::Revealed, FieldType::Pointer { mines_nearby }) =
+            (element.field_status, element.field_type)
+        {
+            canvas.draw_text(
+                self.get_inner_rect(cell_rect),
+                texture_cache,
+                self.game_pointer_color[2],
+                mines_nearby.to_string().as_str(),
+            )?;
         }
+        Ok(())
+    }
 
-        let cell_index_click: (i32, i32) = (
-            x / self.game_square_dimensions.0 as i32,
-            y / self.game_square_dimensions.1 as i32,
+    // Whole board scaled down into the window's corner, with the visible part outlined
+    fn render_minimap(&self, canvas: &mut Canvas<Window>) {
+        let (board_width, board_height) = (*self.board.get_width(), *self.board.get_height());
+        let scale = (MINIMAP_MAX_SIZE / board_width.max(board_height)).max(1);
+        let minimap_rect = Rect::new(
+            self.window_width as i32 - (board_width * scale + MINIMAP_MARGIN) as i32,
+            MINIMAP_MARGIN as i32,
+            board_width * scale,
+            board_height * scale,
         );
 
-        if button == sdl2::mouse::MouseButton::Left {
-            self.flood_reveal(cell_index_click.0, cell_index_click.1);
-        } else if button == sdl2::mouse::MouseButton::Right {
-            self.mark_field(cell_index_click.0, cell_index_click.1);
+        canvas.set_draw_color(self.game_revealed_color[1]);
+        let _ = canvas.fill_rect(minimap_rect);
+
+        let mut unrevealed = Vec::new();
+        let mut marked = Vec::new();
+        let mut mines = Vec::new();
+        let mut pointers = Vec::new();
+        for x in 0..board_width {
+            for y in 0..board_height {
+                let element = self.board.get_fields_array()[self.board.arr2d_arr1d(x as i32, y as i32)];
+                let rect = Rect::new(
+                    minimap_rect.x() + (x * scale) as i32,
+                    minimap_rect.y() + (y * scale) as i32,
+                    scale,
+                    scale,
+                );
+
+                match (element.field_status, element.field_type) {
+                    (FieldStatus::Unrevealed, _) if element.is_marked => marked.push(rect),
+                    (FieldStatus::Unrevealed, _) => unrevealed.push(rect),
+                    (FieldStatus::Revealed, FieldType::Mine) => mines.push(rect),
+                    (FieldStatus::Revealed, FieldType::Pointer { .. }) => pointers.push(rect),
+                    (FieldStatus::Revealed, FieldType::Empty) => {}
+                }
+            }
         }
 
-        self.check_winning_conditions(x, y, button);
-        self.render(canvas, font, texture_creator);
+        for (rects, color) in [
+            (unrevealed, self.game_unrevealed_color[1]),
+            (marked, self.game_marked_color[1]),
+            (mines, self.game_mine_color[1]),
+            (pointers, self.game_pointer_color[1]),
+        ]
+        .iter()
+        {
+            canvas.set_draw_color(*color);
+            let _ = canvas.fill_rects(rects);
+        }
+
+        let (columns, rows) = self.visible_cells();
+        canvas.set_draw_color(Color::from((255u8, 255u8, 255u8)));
+        let _ = canvas.draw_rect(Rect::new(
+            minimap_rect.x() + (columns.0 * scale) as i32,
+            minimap_rect.y() + (rows.0 * scale) as i32,
+            (columns.1 - columns.0) * scale,
+            (rows.1 - rows.0) * scale,
+        ));
+        let _ = canvas.draw_rect(minimap_rect);
     }
 
-    pub fn set_all_visible(&mut self) {
-        for item in self.game_fields_array.iter_mut() {
-            item.field_status = FieldStatus::Revealed;
+    // Revealed cells appear in waves, ordered by their distance from the clicked cell
+    fn animate_reveal(&mut self, origin: (i32, i32), revealed: &[usize]) {
+        let mut distances: HashMap<usize, u32> = revealed.iter().map(|i| (*i, u32::MAX)).collect();
+        let mut queue = VecDeque::new();
+
+        // A chord's origin was revealed before, the wave still starts from it
+        if let Some(origin_index) = self.board.get_cell_index(origin.0, origin.1) {
+            if let Some(distance) = distances.get_mut(&origin_index) {
+                *distance = 0;
+            }
+            queue.push_back((origin.0, origin.1, 0));
+        }
+
+        while let Some((x, y, distance)) = queue.pop_front() {
+            for index in self.board.get_neighbours(self.board.arr2d_arr1d(x, y)) {
+                if distances.get(&index) == Some(&u32::MAX) {
+                    distances.insert(index, distance + 1);
+                    let (x, y) = self.board.arr1d_arr2d(index);
+                    queue.push_back((x, y, distance + 1));
+                }
+            }
+        }
+
+        // Cells the wave can't reach start right away
+        for (index, distance) in distances {
+            let step = if distance == u32::MAX { 0 } else { distance };
+            self.animations.add(index, AnimationKind::Reveal, step);
         }
     }
 
-    fn check_winning_conditions(
+    pub fn click(
         &mut self,
-        x_click: i32,
-        y_click: i32,
+        x: i32,
+        y: i32,
         button: sdl2::mouse::MouseButton,
-    ) {
-        // if loosing
-        if button == sdl2::mouse::MouseButton::Left
-            && self.game_fields_array[self
-                .get_cell_index(
-                    x_click / self.game_square_dimensions.0 as i32,
-                    y_click / self.game_square_dimensions.1 as i32,
-                )
-                .unwrap()]
-            .field_type
-                == FieldType::Mine
-        {
-            // Reveal all mines
-            for item in self.game_fields_array.iter_mut() {
+    ) -> ClickOutcome {
+        if *self.board.get_status() != GameStatus::Playing {
+            return ClickOutcome::Nothing;
+        }
+
+        let cell_index_click = match self.screen_to_cell(x, y) {
+            Some(cell) => cell,
+            None => return ClickOutcome::Nothing,
+        };
+        let action = match button {
+            sdl2::mouse::MouseButton::Left => Action::Reveal,
+            sdl2::mouse::MouseButton::Middle => Action::Chord,
+            sdl2::mouse::MouseButton::Right => Action::Mark,
+            _ => return ClickOutcome::Nothing,
+        };
+
+        self.play(cell_index_click, action)
+    }
+
+    // Bot's move played like a click, None when the bot has nothing left to do
+    pub fn bot_move(&mut self, bot: &mut Bot) -> Option<ClickOutcome> {
+        let next = bot.next_move(&self.board)?;
+        self.game_bot_moves += 1;
+        Some(self.play(next.cell, next.action))
+    }
+
+    fn play(&mut self, cell_index_click: (i32, i32), action: Action) -> ClickOutcome {
+        // Previous action's animations would mix with this one's
+        self.skip_animations();
+        self.game_message = None;
+
+        let outcome = self.board.click(cell_index_click.0, cell_index_click.1, action);
+        let changed_cells = self.board.take_changed_cells();
+        let fields = self.board.get_fields_array();
+
+        if outcome == ClickOutcome::Lost {
+            // Mines go off in rings around the clicked cell
+            for (index, item) in fields.iter().enumerate() {
                 if item.field_type == FieldType::Mine {
-                    item.field_status = FieldStatus::Revealed;
+                    let distance = self
+                        .board
+                        .get_distance(self.board.arr1d_arr2d(index), cell_index_click);
+                    self.animations
+                        .add(index, AnimationKind::Explosion, distance);
+                    self.game_dirty_cells.push(index);
                 }
             }
-
-            self.game_status = GameStatus::Lost;
-        } else {
-            let mut marked_mines = 0;
-            for item in self.game_fields_array.iter() {
-                if item.field_type == FieldType::Mine && item.is_marked {
-                    marked_mines += 1;
+        } else if outcome == ClickOutcome::Flag {
+            for index in changed_cells.iter() {
+                if fields[*index].is_marked {
+                    self.animations.add(*index, AnimationKind::FlagDrop, 0);
                 }
             }
+        }
 
-            if marked_mines == self.game_mines_count {
-                self.game_status = GameStatus::Won;
-            }
+        let revealed: Vec<usize> = changed_cells
+            .iter()
+            .cloned()
+            .filter(|i| {
+                fields[*i].field_status == FieldStatus::Revealed
+                    && fields[*i].field_type != FieldType::Mine
+            })
+            .collect();
+        self.animate_reveal(cell_index_click, &revealed);
+
+        if !changed_cells.is_empty() {
+            self.heatmap.invalidate();
+            self.game_hint = None;
+            self.game_guess_forced =
+                self.board.is_forgiving() && solver::is_guess_forced(&self.board);
         }
+        self.game_dirty_cells.extend(changed_cells);
+
+        if outcome == ClickOutcome::Won || outcome == ClickOutcome::Lost {
+            self.game_results = Some(stats::results(&self.board));
+            self.game_metrics = Some(metrics::analyse(&self.board));
+        }
+        outcome
+    }
+
+    pub fn set_all_visible(&mut self) {
+        self.board.set_all_visible();
+        self.board.take_changed_cells();
+        self.game_full_redraw = true;
     }
 }
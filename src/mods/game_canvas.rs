@@ -1,5 +1,7 @@
 extern crate sdl2;
-use crate::Game;
+use crate::mods::game::Game;
+use crate::mods::error::GameError;
+use crate::mods::texture_cache::TextureCache;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
 use sdl2::render::Canvas;
@@ -7,58 +9,78 @@ use sdl2::video::Window;
 
 pub trait GameCanvas {
     fn draw_square(&mut self, x: u32, y: u32, game: &Game, bg_color: Color, fg_color: Color);
+    // Square with its border drawn into any rect, used by animations
+    fn draw_square_rect(&mut self, outer_rect: Rect, game: &Game, bg_color: Color, fg_color: Color);
     fn draw_text(
         &mut self,
         rect: Rect,
-        font: &sdl2::ttf::Font,
-        texture_creator: &sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+        texture_cache: &mut TextureCache,
         color: Color,
         text: &str,
-    );
+    ) -> Result<(), GameError>;
+    // Text kept at its proportions, drawn glyph by glyph so changing text reuses cached glyphs
+    fn draw_text_line(
+        &mut self,
+        x: i32,
+        y: i32,
+        height: u32,
+        texture_cache: &mut TextureCache,
+        color: Color,
+        text: &str,
+    ) -> Result<u32, GameError>;
 }
-/*                                let surface = font.render(mines_nearby.to_string().as_str()).solid(c).unwrap();
-let texture = canvas.texture_creator().create_texture_from_surface(surface);
-canvas.copy(texture, None, ) */
 
 impl GameCanvas for Canvas<Window> {
     fn draw_square(&mut self, x: u32, y: u32, game: &Game, bg_color: Color, fg_color: Color) {
-        let border_percentage = game.get_game_square_border_percentage();
-        let rect_dimensions = game.get_game_square_dimensions();
-        let outer_rect = Rect::new(
-            x as i32 * rect_dimensions.0 as i32,
-            y as i32 * rect_dimensions.1 as i32,
-            rect_dimensions.0,
-            rect_dimensions.1,
-        );
-        let inner_rect = Rect::new(
-            (border_percentage.0 * rect_dimensions.0 as f32) as i32
-                + x as i32 * rect_dimensions.0 as i32,
-            (border_percentage.1 * rect_dimensions.1 as f32) as i32
-                + y as i32 * rect_dimensions.1 as i32,
-            rect_dimensions.0 - (border_percentage.0 * rect_dimensions.0 as f32 * 2f32) as u32,
-            rect_dimensions.1 - (border_percentage.1 * rect_dimensions.1 as f32 * 2f32) as u32,
-        );
+        self.draw_square_rect(game.get_cell_rect(x, y), game, bg_color, fg_color);
+    }
+
+    fn draw_square_rect(&mut self, outer_rect: Rect, game: &Game, bg_color: Color, fg_color: Color) {
+        let inner_rect = game.get_inner_rect(outer_rect);
         // bigger rect
         self.set_draw_color(bg_color);
-        self.fill_rect(outer_rect);
-        
+        let _ = self.fill_rect(outer_rect);
+
         // smaller rect
         self.set_draw_color(fg_color);
-        self.fill_rect(inner_rect);
+        let _ = self.fill_rect(inner_rect);
     }
+
     fn draw_text(
         &mut self,
-        rect : Rect,
-        font: &sdl2::ttf::Font,
-        texture_creator: &sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+        rect: Rect,
+        texture_cache: &mut TextureCache,
+        color: Color,
+        text: &str,
+    ) -> Result<(), GameError> {
+        let texture = texture_cache.get(text, color)?;
+        self.copy(texture, None, Some(rect))
+            .map_err(GameError::Render)
+    }
+
+    fn draw_text_line(
+        &mut self,
+        x: i32,
+        y: i32,
+        height: u32,
+        texture_cache: &mut TextureCache,
         color: Color,
         text: &str,
-    ) {
-        let surface = font
-            .render(text)
-            .solid(color)
-            .unwrap();
-        let texture = texture_creator.create_texture_from_surface(&surface).unwrap();
-        self.copy(&texture, None, Some(rect));
+    ) -> Result<u32, GameError> {
+        let mut glyph_buffer = [0u8; 4];
+        let mut width = 0;
+        for glyph in text.chars() {
+            let texture = texture_cache.get(glyph.encode_utf8(&mut glyph_buffer), color)?;
+            let query = texture.query();
+            let glyph_width = query.width * height / query.height;
+            self.copy(
+                texture,
+                None,
+                Some(Rect::new(x + width as i32, y, glyph_width, height)),
+            )
+            .map_err(GameError::Render)?;
+            width += glyph_width;
+        }
+        Ok(width)
     }
 }
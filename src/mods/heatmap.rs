@@ -0,0 +1,114 @@
+extern crate sdl2;
+use crate::mods::error::GameError;
+use crate::mods::game_canvas::GameCanvas;
+use crate::mods::texture_cache::TextureCache;
+use minesweeper::engine::board::Board;
+use minesweeper::engine::field::*;
+use minesweeper::engine::probability::{self, Probabilities};
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::video::Window;
+
+const TINT_ALPHA: u8 = 128;
+// Squares smaller than this are only tinted, the percentage wouldn't be readable
+const PERCENTAGE_MIN_SQUARE_SIZE: u32 = 28;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatmapMode {
+    Off,
+    Tint,
+    // Tint with the percentage written over it
+    Percentages,
+}
+
+// Mine probabilities drawn over hidden cells, from green for safe to red for a sure mine
+pub struct Heatmap {
+    mode: HeatmapMode,
+    // Computed on the first frame after the board changed
+    probabilities: Option<Probabilities>,
+}
+
+impl Heatmap {
+    pub fn new() -> Heatmap {
+        Heatmap {
+            mode: HeatmapMode::Off,
+            probabilities: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode != HeatmapMode::Off
+    }
+
+    // Off, tint, tint with percentages and off again
+    pub fn cycle(&mut self) {
+        self.mode = match self.mode {
+            HeatmapMode::Off => HeatmapMode::Tint,
+            HeatmapMode::Tint => HeatmapMode::Percentages,
+            HeatmapMode::Percentages => HeatmapMode::Off,
+        };
+    }
+
+    pub fn invalidate(&mut self) {
+        self.probabilities = None;
+    }
+
+    pub fn render(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        texture_cache: &mut TextureCache,
+        board: &Board,
+        cells: &[(usize, Rect)],
+    ) -> Result<(), GameError> {
+        let probabilities = self
+            .probabilities
+            .get_or_insert_with(|| probability::mine_probabilities(board));
+        let fields = board.get_fields_array();
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        for (index, rect) in cells.iter() {
+            let field = fields[*index];
+            if field.field_status == FieldStatus::Revealed || field.is_marked {
+                continue;
+            }
+
+            if let Some(probability) = probabilities.cells[*index] {
+                let color = gradient(probability);
+                canvas.set_draw_color(Color::RGBA(color.r, color.g, color.b, TINT_ALPHA));
+                let _ = canvas.fill_rect(*rect);
+
+                if self.mode == HeatmapMode::Percentages
+                    && rect.height() >= PERCENTAGE_MIN_SQUARE_SIZE
+                {
+                    // Estimated chances are marked, the frontier was too big to enumerate
+                    let text = if probabilities.is_exact {
+                        format!("{:.0}", probability * 100f64)
+                    } else {
+                        format!("~{:.0}", probability * 100f64)
+                    };
+                    let height = rect.height() / 3;
+                    let width = texture_cache.text_width(&text, height)?;
+                    canvas.draw_text_line(
+                        rect.center().x() - width as i32 / 2,
+                        rect.center().y() - height as i32 / 2,
+                        height,
+                        texture_cache,
+                        Color::from((255u8, 255u8, 255u8)),
+                        &text,
+                    )?;
+                }
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+        Ok(())
+    }
+}
+
+// Green through yellow to red
+fn gradient(probability: f64) -> Color {
+    let probability = probability.clamp(0f64, 1f64);
+    let red = (probability * 2f64).min(1f64);
+    let green = ((1f64 - probability) * 2f64).min(1f64);
+    Color::RGB((red * 255f64) as u8, (green * 200f64) as u8, 0)
+}
@@ -1,3 +1,11 @@
 pub mod game;
 pub mod game_canvas;
-pub mod field;
+pub mod camera;
+pub mod texture_cache;
+pub mod frame_counter;
+pub mod animation;
+pub mod options;
+pub mod audio;
+pub mod error;
+pub mod assets;
+pub mod heatmap;
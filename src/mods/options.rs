@@ -0,0 +1,93 @@
+use crate::mods::error::GameError;
+use std::env;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "Usage: minesweeper [--no-animations] [--mute] [--font <file.ttf>] [--layout <file>] [--save-layout <file>] [--scores <file>] [--bot-speed <moves per second>] [--forgiving] [--torus] [--no-texture-cache]";
+
+// Command line options
+pub struct Options {
+    pub animations: bool,
+    pub muted: bool,
+    // Font file used instead of the built-in one
+    pub font: Option<PathBuf>,
+    // Mine layout played instead of a random one, .mbf or a text grid
+    pub layout: Option<PathBuf>,
+    // Where S saves the layout being played, the extension picks the format
+    pub save_layout: PathBuf,
+    // File every finished game is added to with its layout's difficulty, none by default
+    pub scores: Option<PathBuf>,
+    // How fast the bot plays once B starts it
+    pub bot_speed: u32,
+    // Forced guesses never lose the game
+    pub forgiving: bool,
+    // Edges of the board wrap around
+    pub torus: bool,
+    // Rendering without cached text textures, to compare against the cached one
+    pub texture_cache: bool,
+}
+
+impl Options {
+    pub fn from_args() -> Result<Options, GameError> {
+        let mut options = Options {
+            animations: true,
+            muted: false,
+            font: None,
+            layout: None,
+            save_layout: PathBuf::from("layout.mbf"),
+            scores: None,
+            bot_speed: 10,
+            forgiving: false,
+            torus: false,
+            texture_cache: true,
+        };
+
+        let mut arguments = env::args().skip(1);
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "--no-animations" => options.animations = false,
+                "--mute" => options.muted = true,
+                "--forgiving" => options.forgiving = true,
+                "--torus" => options.torus = true,
+                "--no-texture-cache" => options.texture_cache = false,
+                "--font" => {
+                    options.font = Some(PathBuf::from(Options::value(&mut arguments, &argument)?))
+                }
+                "--layout" => {
+                    options.layout = Some(PathBuf::from(Options::value(&mut arguments, &argument)?))
+                }
+                "--save-layout" => {
+                    options.save_layout = PathBuf::from(Options::value(&mut arguments, &argument)?)
+                }
+                "--scores" => {
+                    options.scores = Some(PathBuf::from(Options::value(&mut arguments, &argument)?))
+                }
+                "--bot-speed" => {
+                    let value = Options::value(&mut arguments, &argument)?;
+                    options.bot_speed = match value.parse() {
+                        Ok(speed) if speed > 0 => speed,
+                        _ => {
+                            return Err(GameError::Arguments(format!(
+                                "Invalid value for {}: {}",
+                                argument, value
+                            )))
+                        }
+                    };
+                }
+                _ => {
+                    return Err(GameError::Arguments(format!(
+                        "Unknown argument: {}",
+                        argument
+                    )))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn value(arguments: &mut impl Iterator<Item = String>, name: &str) -> Result<String, GameError> {
+        arguments
+            .next()
+            .ok_or_else(|| GameError::Arguments(format!("Missing value for {}", name)))
+    }
+}
@@ -0,0 +1,106 @@
+extern crate sdl2;
+use crate::mods::error::GameError;
+use sdl2::pixels::Color;
+use sdl2::render::{Texture, TextureCreator};
+use sdl2::ttf::Font;
+use sdl2::video::WindowContext;
+use std::collections::HashMap;
+
+// Rendered text textures, keyed by color and text, so digits aren't rendered on every frame
+pub struct TextureCache<'a> {
+    font: &'a Font<'a, 'a>,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    textures: HashMap<(u8, u8, u8, u8), HashMap<String, Texture<'a>>>,
+    // Window sized render target keeping the drawn board between frames
+    board_texture: Option<Texture<'a>>,
+    enabled: bool,
+}
+
+impl<'a> TextureCache<'a> {
+    pub fn new(
+        font: &'a Font<'a, 'a>,
+        texture_creator: &'a TextureCreator<WindowContext>,
+    ) -> TextureCache<'a> {
+        TextureCache {
+            font,
+            texture_creator,
+            textures: HashMap::new(),
+            board_texture: None,
+            enabled: true,
+        }
+    }
+
+    pub fn get(&mut self, text: &str, color: Color) -> Result<&Texture<'a>, GameError> {
+        // Disabled cache keeps only the texture being drawn, same as rendering it every time
+        if !self.enabled {
+            self.textures.clear();
+        }
+
+        let textures = self.textures.entry(color.rgba()).or_default();
+        if !textures.contains_key(text) {
+            let surface = self
+                .font
+                .render(text)
+                .solid(color)
+                .map_err(|e| GameError::Render(e.to_string()))?;
+            let texture = self
+                .texture_creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| GameError::Render(e.to_string()))?;
+            textures.insert(text.to_string(), texture);
+        }
+        Ok(&textures[text])
+    }
+
+    // Width of the text drawn with GameCanvas::draw_text_line
+    pub fn text_width(&mut self, text: &str, height: u32) -> Result<u32, GameError> {
+        let mut glyph_buffer = [0u8; 4];
+        let mut width = 0;
+        for glyph in text.chars() {
+            let query = self
+                .get(glyph.encode_utf8(&mut glyph_buffer), Color::from((255u8, 255u8, 255u8)))?
+                .query();
+            width += query.width * height / query.height;
+        }
+        Ok(width)
+    }
+
+    // Board texture is taken out while it's drawn on, so text can still be fetched meanwhile.
+    // Second value tells whether the texture was just created and holds no board yet.
+    // Returns None when render targets aren't supported.
+    pub fn take_board_texture(&mut self, width: u32, height: u32) -> Option<(Texture<'a>, bool)> {
+        if let Some(texture) = self.board_texture.take() {
+            let query = texture.query();
+            if query.width == width && query.height == height {
+                return Some((texture, false));
+            }
+        }
+
+        self.texture_creator
+            .create_texture_target(None, width, height)
+            .ok()
+            .map(|texture| (texture, true))
+    }
+
+    pub fn return_board_texture(&mut self, texture: Texture<'a>) {
+        self.board_texture = Some(texture);
+    }
+
+    // Has to be called whenever colors or sizes of the rendered text change
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.values().map(|textures| textures.len()).sum()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.clear();
+    }
+}